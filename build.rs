@@ -0,0 +1,29 @@
+use std::process::Command;
+
+fn main() {
+    // Embed the commit and compiler used for the build (shown by `gj version --verbose`)
+    let commit = command_output("git", &["rev-parse", "--short", "HEAD"]);
+    println!("cargo:rustc-env=GJ_BUILD_COMMIT={}", commit);
+
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    println!(
+        "cargo:rustc-env=GJ_RUSTC_VERSION={}",
+        command_output(&rustc, &["--version"])
+    );
+
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs/heads");
+}
+
+/// First line of a command's stdout, or "unknown" if it fails
+fn command_output(program: &str, args: &[&str]) -> String {
+    Command::new(program)
+        .args(args)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8(o.stdout).ok())
+        .and_then(|s| s.lines().next().map(|l| l.trim().to_string()))
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
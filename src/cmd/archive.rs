@@ -0,0 +1,214 @@
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::config::Config;
+use crate::error::GjError;
+use crate::git;
+use crate::state::{self, WorktreeState};
+
+/// Execute the `gj archive` command
+pub fn run(name: Option<String>, respect_gitignore: bool) -> Result<()> {
+    let state = match name {
+        Some(name) => super::cd::find_worktree(&name)?,
+        None => WorktreeState::load_current()?.ok_or(GjError::NotInWorktree)?,
+    };
+
+    let config = Config::load()?;
+    let archive_path = archive_worktree(&state, &config, respect_gitignore)?;
+
+    println!("{}", archive_path.display());
+
+    Ok(())
+}
+
+/// Create a `.tar.gz` snapshot of a worktree (excluding `.git`) in the archive directory
+pub fn archive_worktree(
+    state: &WorktreeState,
+    config: &Config,
+    respect_gitignore: bool,
+) -> Result<PathBuf> {
+    let archive_dir = config.get_archive_dir();
+    fs::create_dir_all(&archive_dir).with_context(|| {
+        format!(
+            "Failed to create archive directory: {}",
+            archive_dir.display()
+        )
+    })?;
+
+    let archive_path = unique_path(archive_dir.join(archive_file_name(&state.branch, Utc::now())));
+
+    if respect_gitignore {
+        // Tracked and untracked files, minus ignored ones; skip tracked files deleted on disk
+        let files: Vec<String> = git::list_files(&state.worktree_path)?
+            .into_iter()
+            .filter(|f| state.worktree_path.join(f).exists())
+            .collect();
+        create_tar_from_list(&state.worktree_path, &archive_path, &files)?;
+    } else {
+        create_tar(&state.worktree_path, &archive_path)?;
+    }
+
+    eprintln!("Archived worktree: {}", state::display_path(&archive_path));
+
+    Ok(archive_path)
+}
+
+/// Archive file name from a branch and timestamp
+/// Example: gj/20260205_hello -> gj-20260205_hello_20260205_153000.tar.gz
+fn archive_file_name(branch: &str, now: DateTime<Utc>) -> String {
+    format!(
+        "{}_{}.tar.gz",
+        branch.replace('/', "-"),
+        now.format("%Y%m%d_%H%M%S")
+    )
+}
+
+/// Append a numeric suffix if the path already exists, so archives are never overwritten
+/// Example: snapshot.tar.gz -> snapshot-1.tar.gz
+fn unique_path(path: PathBuf) -> PathBuf {
+    if !path.exists() {
+        return path;
+    }
+
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let stem = file_name.strip_suffix(".tar.gz").unwrap_or(&file_name);
+
+    (1..)
+        .map(|i| path.with_file_name(format!("{}-{}.tar.gz", stem, i)))
+        .find(|candidate| !candidate.exists())
+        .unwrap()
+}
+
+/// Archive a whole directory, excluding `.git`
+fn create_tar(source: &Path, archive_path: &Path) -> Result<()> {
+    let output = Command::new("tar")
+        .arg("-czf")
+        .arg(archive_path)
+        .arg("--exclude=.git")
+        .arg("-C")
+        .arg(source)
+        .arg(".")
+        .output()
+        .context("Failed to execute tar")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("Failed to create archive: {}", stderr.trim());
+    }
+
+    Ok(())
+}
+
+/// Archive a list of files relative to a directory
+fn create_tar_from_list(source: &Path, archive_path: &Path, files: &[String]) -> Result<()> {
+    let mut child = Command::new("tar")
+        .arg("-czf")
+        .arg(archive_path)
+        .arg("-C")
+        .arg(source)
+        .args(["--null", "-T", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to execute tar")?;
+
+    {
+        let mut stdin = child.stdin.take().context("Failed to open tar stdin")?;
+        for file in files {
+            stdin.write_all(file.as_bytes())?;
+            stdin.write_all(b"\0")?;
+        }
+    }
+
+    let output = child.wait_with_output().context("Failed to wait for tar")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("Failed to create archive: {}", stderr.trim());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use tempfile::TempDir;
+
+    /// List the entries of a .tar.gz archive
+    fn list_archive(archive_path: &Path) -> Vec<String> {
+        let output = Command::new("tar")
+            .arg("-tzf")
+            .arg(archive_path)
+            .output()
+            .expect("Failed to list archive");
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|l| l.trim_start_matches("./").to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_archive_file_name() {
+        let now = Utc.with_ymd_and_hms(2026, 2, 5, 15, 30, 0).unwrap();
+        assert_eq!(
+            archive_file_name("gj/20260205_hello", now),
+            "gj-20260205_hello_20260205_153000.tar.gz"
+        );
+        assert_eq!(
+            archive_file_name("main", now),
+            "main_20260205_153000.tar.gz"
+        );
+    }
+
+    #[test]
+    fn test_unique_path() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("snapshot.tar.gz");
+        assert_eq!(unique_path(path.clone()), path);
+
+        fs::write(&path, "").unwrap();
+        assert_eq!(
+            unique_path(path.clone()),
+            dir.path().join("snapshot-1.tar.gz")
+        );
+
+        fs::write(dir.path().join("snapshot-1.tar.gz"), "").unwrap();
+        assert_eq!(unique_path(path), dir.path().join("snapshot-2.tar.gz"));
+    }
+
+    #[test]
+    fn test_create_tar_excludes_git() {
+        let source = TempDir::new().unwrap();
+        fs::create_dir(source.path().join(".git")).unwrap();
+        fs::write(source.path().join(".git").join("HEAD"), "ref").unwrap();
+        fs::write(source.path().join("notes.txt"), "wip").unwrap();
+
+        let out = TempDir::new().unwrap();
+        let archive_path = out.path().join("snapshot.tar.gz");
+        create_tar(source.path(), &archive_path).unwrap();
+
+        let entries = list_archive(&archive_path);
+        assert!(entries.iter().any(|e| e == "notes.txt"));
+        assert!(!entries.iter().any(|e| e.starts_with(".git")));
+    }
+
+    #[test]
+    fn test_create_tar_from_list() {
+        let source = TempDir::new().unwrap();
+        fs::write(source.path().join("keep.txt"), "keep").unwrap();
+        fs::write(source.path().join("skip.log"), "skip").unwrap();
+
+        let out = TempDir::new().unwrap();
+        let archive_path = out.path().join("snapshot.tar.gz");
+        create_tar_from_list(source.path(), &archive_path, &["keep.txt".to_string()]).unwrap();
+
+        assert_eq!(list_archive(&archive_path), vec!["keep.txt"]);
+    }
+}
@@ -0,0 +1,29 @@
+use anyhow::{Context, Result};
+
+use crate::config::Config;
+use crate::git;
+
+/// Execute the `gj base-dir` command
+pub fn run(repo: Option<String>) -> Result<()> {
+    let config = Config::load()?;
+
+    // A named repository, else the current one; outside a repository only defaults apply
+    let repo_root = match &repo {
+        Some(name) => {
+            let repo_config = config.repos.get(name).with_context(|| {
+                format!("Repository '{}' is not configured in the config file", name)
+            })?;
+            Some(repo_config.expanded_path())
+        }
+        None => git::get_repo_root().ok(),
+    };
+
+    let repo_config = match &repo_root {
+        Some(root) => config.resolve_repo(root)?,
+        None => None,
+    };
+
+    println!("{}", config.get_base_dir(repo_config.as_ref()).display());
+
+    Ok(())
+}
@@ -1,58 +1,114 @@
 use anyhow::{bail, Context, Result};
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
 
+use crate::config::Config;
+use crate::error::GjError;
 use crate::state::{self, WorktreeState};
 
-/// Execute the `gj cd` command
-pub fn run(target: Option<String>) -> Result<()> {
-    match target.as_deref() {
-        Some("@") => cd_to_origin(),
-        Some(name) => cd_to_worktree(name),
-        None => cd_interactive(),
+/// Execute the `gj cd` command.
+/// With `print_cmd`, prints a `cd '<path>'` command for `eval` instead of the bare path.
+pub fn run(target: Option<String>, print_cmd: bool) -> Result<()> {
+    if let Ok(config) = Config::load() {
+        super::list::auto_prune(&config);
     }
+
+    let path = match target.as_deref() {
+        Some("@") => cd_to_origin()?,
+        Some(target) if target.starts_with('@') => cd_to_repo(&target[1..])?,
+        Some(name) => cd_to_worktree(name)?,
+        None => cd_interactive()?,
+    };
+
+    if print_cmd {
+        println!("{}", format_cd_command(&path));
+    } else {
+        println!("{}", path.display());
+    }
+    Ok(())
+}
+
+/// Format a `cd` command for `eval`, single-quoting the path
+fn format_cd_command(path: &Path) -> String {
+    format!("cd {}", crate::shell::single_quote(&path.to_string_lossy()))
 }
 
 /// Navigate to the origin repository of the current worktree
-fn cd_to_origin() -> Result<()> {
-    let state = WorktreeState::load_current()?
-        .ok_or_else(|| anyhow::anyhow!("Not in a gj-managed worktree"))?;
+fn cd_to_origin() -> Result<PathBuf> {
+    let state = WorktreeState::load_current()?.ok_or(GjError::NotInWorktree)?;
+    if !state.origin_repo.is_dir() {
+        return Err(GjError::OriginMissing(state.origin_repo).into());
+    }
+    Ok(state.origin_repo)
+}
 
-    println!("{}", state.origin_repo.display());
-    Ok(())
+/// Navigate to the root of a registered repository (`@<name>`)
+fn cd_to_repo(name: &str) -> Result<PathBuf> {
+    let config = Config::load_required()?;
+    config.registered_repo_path(name)
 }
 
 /// Navigate to a worktree by name
-fn cd_to_worktree(name: &str) -> Result<()> {
+fn cd_to_worktree(name: &str) -> Result<PathBuf> {
+    // The same PR number may be checked out in several repositories: let the user pick
+    if name.parse::<u32>().is_ok() {
+        let existing: Vec<_> = state::list_all_states()?
+            .into_iter()
+            .filter(|s| matches_name(s, name) && s.worktree_path.exists())
+            .collect();
+        if existing.len() > 1 {
+            eprintln!("Multiple worktrees match PR #{}:", name);
+            return select_from(existing);
+        }
+    }
+
+    Ok(find_worktree(name)?.worktree_path)
+}
+
+/// Whether a worktree matches a name: its branch, last path segment, or trailing segments
+/// (repo/branch). A bare number also matches the worktree of that PR.
+fn matches_name(state: &WorktreeState, name: &str) -> bool {
+    // Match against branch (the directory may differ, e.g. `gj new --dir`)
+    if state.branch == name {
+        return true;
+    }
+    let path = &state.worktree_path;
+    let last = path.file_name().and_then(|n| n.to_str());
+    // Match against last segment
+    if last == Some(name) {
+        return true;
+    }
+    // Match a PR number by recorded `pr_number` or the `pr-<n>` directory
+    if let Ok(number) = name.parse::<u32>() {
+        if state.pr_number == Some(number) || last == Some(format!("pr-{}", number).as_str()) {
+            return true;
+        }
+    }
+    // Match against last two segments (repo/branch)
+    let display_name = get_display_name(path);
+    display_name == name || display_name.ends_with(&format!("/{}", name))
+}
+
+/// Find the single existing managed worktree matching a name
+pub fn find_worktree(name: &str) -> Result<WorktreeState> {
     let states = state::list_all_states()?;
 
-    // Find worktree matching the name (check last path segment or last two segments)
-    let matching: Vec<_> = states
-        .iter()
-        .filter(|s| {
-            let path = &s.worktree_path;
-            // Match against last segment
-            if let Some(last) = path.file_name().and_then(|n| n.to_str()) {
-                if last == name {
-                    return true;
-                }
-            }
-            // Match against last two segments (repo/branch)
-            let display_name = get_display_name(path);
-            display_name == name || display_name.ends_with(&format!("/{}", name))
-        })
+    let mut matching: Vec<_> = states
+        .into_iter()
+        .filter(|s| matches_name(s, name))
         .collect();
 
     match matching.len() {
         0 => bail!("No worktree found matching '{}'", name),
         1 => {
-            let state = matching[0];
+            let state = matching.remove(0);
             if !state.worktree_path.exists() {
                 bail!(
                     "Worktree no longer exists at {}",
                     state.worktree_path.display()
                 );
             }
-            println!("{}", state.worktree_path.display());
-            Ok(())
+            Ok(state)
         }
         _ => {
             eprintln!("Multiple worktrees match '{}'. Please be more specific:", name);
@@ -65,7 +121,7 @@ fn cd_to_worktree(name: &str) -> Result<()> {
 }
 
 /// Interactive selection of worktree
-fn cd_interactive() -> Result<()> {
+fn cd_interactive() -> Result<PathBuf> {
     let states = state::list_all_states()?;
 
     if states.is_empty() {
@@ -82,8 +138,13 @@ fn cd_interactive() -> Result<()> {
         bail!("No existing worktrees found.");
     }
 
+    select_from(existing_states)
+}
+
+/// Let the user select one of the worktrees, returning its path
+fn select_from(states: Vec<WorktreeState>) -> Result<PathBuf> {
     // Build selection options
-    let options: Vec<String> = existing_states
+    let options: Vec<String> = states
         .iter()
         .map(|s| {
             let display_name = get_display_name(&s.worktree_path);
@@ -91,32 +152,63 @@ fn cd_interactive() -> Result<()> {
         })
         .collect();
 
-    let selection = inquire::Select::new("Select worktree:", options)
-        .prompt()
-        .context("Failed to get selection")?;
+    let selected_index = select_worktree(options)?;
 
-    // Find the selected state
-    let selected_index = existing_states
-        .iter()
-        .position(|s| {
-            let display_name = get_display_name(&s.worktree_path);
-            let option = format!("{} ({})", display_name, s.branch);
-            option == selection
-        })
-        .unwrap();
+    Ok(states[selected_index].worktree_path.clone())
+}
 
-    println!("{}", existing_states[selected_index].worktree_path.display());
-    Ok(())
+/// Select an option, falling back to a numbered prompt when the fuzzy selector is unusable
+fn select_worktree(options: Vec<String>) -> Result<usize> {
+    if !std::io::stdin().is_terminal() {
+        return select_numbered(&options);
+    }
+
+    match inquire::Select::new("Select worktree:", options.clone()).raw_prompt() {
+        Ok(selection) => Ok(selection.index),
+        Err(
+            e @ (inquire::InquireError::OperationCanceled
+            | inquire::InquireError::OperationInterrupted),
+        ) => Err(e).context("Failed to get selection"),
+        Err(_) => select_numbered(&options),
+    }
+}
+
+/// Print a numbered list to stderr and read the selected number from stdin
+fn select_numbered(options: &[String]) -> Result<usize> {
+    for (i, option) in options.iter().enumerate() {
+        eprintln!("{:>3}) {}", i + 1, option);
+    }
+    eprint!("Select worktree [1-{}]: ", options.len());
+    std::io::stderr().flush()?;
+
+    let mut input = String::new();
+    std::io::stdin()
+        .read_line(&mut input)
+        .context("Failed to read selection")?;
+
+    parse_selection(&input, options.len())
+        .with_context(|| format!("Invalid selection: '{}'", input.trim()))
 }
 
-/// Get the display name from a worktree path (everything after "worktrees/")
-/// Example: ~/.gj/worktrees/mirakui/my_repo/gj/20260205_hello -> mirakui/my_repo/gj/20260205_hello
+/// Parse a 1-based selection number into a 0-based index
+fn parse_selection(input: &str, len: usize) -> Option<usize> {
+    let number: usize = input.trim().parse().ok()?;
+    if number == 0 || number > len {
+        return None;
+    }
+    Some(number - 1)
+}
+
+/// Get the display name from a worktree path (everything after "worktrees/", without the github.com segment)
+/// Example: ~/.gj/worktrees/github.com/mirakui/my_repo/gj/20260205_hello -> mirakui/my_repo/gj/20260205_hello
 fn get_display_name(path: &std::path::Path) -> String {
     let path_str = path.to_string_lossy();
 
     // Find "worktrees/" in the path and return everything after it
     if let Some(idx) = path_str.find("worktrees/") {
-        return path_str[idx + "worktrees/".len()..].to_string();
+        let name = &path_str[idx + "worktrees/".len()..];
+        let github_prefix = format!("{}/", crate::git::GITHUB_HOST);
+        return name.strip_prefix(&github_prefix).unwrap_or(name).to_string();
     }
 
     // Fallback: return last 2 components if "worktrees/" not found
@@ -156,6 +248,57 @@ mod tests {
         assert_eq!(get_display_name(&path), "mirakui/my_repo/pr-123");
     }
 
+    #[test]
+    fn test_matches_name() {
+        let mut state = WorktreeState::new(
+            PathBuf::from("/Users/test/.gj/worktrees/mirakui/my_repo/pr-123"),
+            PathBuf::from("/dev/my_repo"),
+            "fix-login".to_string(),
+        );
+        assert!(matches_name(&state, "fix-login"));
+        assert!(matches_name(&state, "pr-123"));
+        assert!(matches_name(&state, "my_repo/pr-123"));
+        assert!(matches_name(&state, "123"));
+        assert!(!matches_name(&state, "12"));
+
+        // A renamed PR worktree is still found by its recorded number
+        state.worktree_path = PathBuf::from("/Users/test/.gj/worktrees/mirakui/my_repo/review");
+        assert!(!matches_name(&state, "123"));
+        state.pr_number = Some(123);
+        assert!(matches_name(&state, "123"));
+    }
+
+    #[test]
+    fn test_format_cd_command() {
+        assert_eq!(
+            format_cd_command(Path::new("/wt/my repo/pr-1")),
+            "cd '/wt/my repo/pr-1'"
+        );
+        assert_eq!(
+            format_cd_command(Path::new("/wt/it's")),
+            "cd '/wt/it'\\''s'"
+        );
+    }
+
+    #[test]
+    fn test_parse_selection() {
+        assert_eq!(parse_selection("1\n", 3), Some(0));
+        assert_eq!(parse_selection(" 3 ", 3), Some(2));
+        assert_eq!(parse_selection("0", 3), None);
+        assert_eq!(parse_selection("4", 3), None);
+        assert_eq!(parse_selection("abc", 3), None);
+        assert_eq!(parse_selection("", 3), None);
+    }
+
+    #[test]
+    fn test_get_display_name_with_host() {
+        let path = PathBuf::from("/Users/test/.gj/worktrees/github.com/mirakui/my_repo/pr-123");
+        assert_eq!(get_display_name(&path), "mirakui/my_repo/pr-123");
+
+        let path = PathBuf::from("/Users/test/.gj/worktrees/ghe.example.com/mirakui/my_repo/pr-1");
+        assert_eq!(get_display_name(&path), "ghe.example.com/mirakui/my_repo/pr-1");
+    }
+
     #[test]
     fn test_get_display_name_fallback() {
         // Without "worktrees/" in path, falls back to last 2 components
@@ -1,37 +1,87 @@
 use anyhow::{bail, Context, Result};
+use std::path::Path;
 
+use super::CreateOptions;
 use crate::config::Config;
 use crate::git;
 use crate::hooks;
 use crate::state::WorktreeState;
 
 /// Execute the `gj checkout` command
-pub fn run(remote_branch: String) -> Result<()> {
+pub fn run(
+    remote_branches: Vec<String>,
+    offline: bool,
+    create: bool,
+    options: CreateOptions,
+) -> Result<()> {
+    match remote_branches.as_slice() {
+        [remote_branch] => checkout(remote_branch, offline, create, &options, true),
+        _ => run_batch(&remote_branches, offline, create, &options),
+    }
+}
+
+/// Create a worktree for each branch, continuing past failures.
+/// There is no single directory to change into, so no path is printed for the shell wrapper.
+fn run_batch(
+    remote_branches: &[String],
+    offline: bool,
+    create: bool,
+    options: &CreateOptions,
+) -> Result<()> {
+    if options.porcelain {
+        bail!("--porcelain can only be used with a single branch");
+    }
+
+    let mut failed = Vec::new();
+    for (i, remote_branch) in remote_branches.iter().enumerate() {
+        eprintln!("[{}/{}] {}", i + 1, remote_branches.len(), remote_branch);
+        if let Err(e) = checkout(remote_branch, offline, create, options, false) {
+            eprintln!("Failed: {:#}", e);
+            failed.push(remote_branch.as_str());
+        }
+    }
+
+    eprintln!(
+        "Checked out {} of {} branches",
+        remote_branches.len() - failed.len(),
+        remote_branches.len()
+    );
+    if !failed.is_empty() {
+        bail!("Failed to check out: {}", failed.join(", "));
+    }
+    Ok(())
+}
+
+/// Create a worktree for one remote branch. With `print_path`, its path is printed for
+/// the shell wrapper.
+fn checkout(
+    remote_branch: &str,
+    offline: bool,
+    create: bool,
+    options: &CreateOptions,
+    print_path: bool,
+) -> Result<()> {
     // Get the git repository root
     let git_root = git::get_repo_root().context("Must be run inside a git repository")?;
+    let git_root = super::resolve_origin_root(git_root)?;
 
     // Load configuration (requires config file to exist)
     let config = Config::load_required()?;
 
-    // Find the repository configuration (optional - works without registration)
-    let repo_config = config.find_repo(&git_root).map(|(_, cfg)| cfg);
+    // Find the repository configuration, merged with .gj.toml (optional - works without registration)
+    let repo_config = config.resolve_repo(&git_root)?;
+    let repo_config = repo_config.as_ref();
 
     // Get GitHub repository info from remote URL
     let github_repo = git::get_github_repo_info()?;
 
     // Parse the branch name (remove origin/ prefix if present)
-    let branch_name = parse_branch_name(&remote_branch);
+    let branch_name = parse_branch_name(remote_branch);
 
-    // Fetch the branch from origin
-    eprintln!("Fetching branch '{}'...", branch_name);
-    git::fetch_branch(branch_name)?;
-
-    // Generate worktree path: {base_dir}/{owner}/{repo}/{branch_name}
-    let base_dir = config.get_base_dir(repo_config);
-    let worktree_path = base_dir
-        .join(&github_repo.owner)
-        .join(&github_repo.repo)
-        .join(branch_name);
+    // Generate worktree path: {base_dir}/{host}/{owner}/{repo}/{branch_name}
+    let worktree_path =
+        super::repo_worktree_dir(&config, repo_config, &github_repo, options)?.join(branch_name);
+    super::check_path_length(&worktree_path)?;
 
     // Check if worktree path already exists
     if worktree_path.exists() {
@@ -42,9 +92,31 @@ pub fn run(remote_branch: String) -> Result<()> {
         );
     }
 
-    // Create the worktree at origin/{branch}
     let git_ref = format!("origin/{}", branch_name);
-    git::worktree_add_at_ref(&worktree_path, &git_ref)?;
+    if options.dry_run {
+        let hooks = config.get_hooks(repo_config);
+        super::print_dry_run(branch_name, &worktree_path, &git_ref, &hooks);
+        return Ok(());
+    }
+
+    // Fetch the branch from origin
+    if !offline {
+        eprintln!("Fetching branch '{}'...", branch_name);
+    }
+    match super::fetch_or_use_local(branch_name, offline, &git_root) {
+        // Create the worktree at origin/{branch}
+        Ok(()) => git::worktree_add_at_ref(&worktree_path, &git_ref)?,
+        Err(e) if create => {
+            eprintln!("Warning: {:#}", e);
+            create_local_branch_worktree(branch_name, &worktree_path, &git_root)?;
+        }
+        Err(e) => {
+            return Err(e.context(format!(
+                "Could not get origin/{}. Use --create to create the branch locally.",
+                branch_name
+            )))
+        }
+    }
 
     // Save state
     let state = WorktreeState::new(
@@ -52,22 +124,43 @@ pub fn run(remote_branch: String) -> Result<()> {
         git_root.clone(),
         branch_name.to_string(),
     );
-    state.save()?;
+    super::save_state(&state, options)?;
+
+    // Bring over local-only files such as .env before hooks that may rely on them
+    super::copy_gitignored(&config, &git_root, &worktree_path);
 
     // Execute hooks
     let all_hooks = config.get_hooks(repo_config);
-    if let Err(e) = hooks::execute_hooks(&all_hooks, &git_root, &worktree_path) {
+    let hook_env = config.get_hook_env(repo_config);
+    let parallel = config.default.parallel_hooks;
+    let result = hooks::execute_hooks(&all_hooks, &git_root, &worktree_path, &hook_env, parallel);
+    if let Err(e) = result {
         eprintln!("Warning: Hook failed: {}", e);
     }
 
     // Output the worktree path
     eprintln!("Created worktree: {}", crate::state::display_path(&worktree_path));
     eprintln!("Branch: {}", branch_name);
-    println!("{}", worktree_path.display());
+    if print_path {
+        let no_cd = options.skips_cd(&config, repo_config);
+        super::print_created(&worktree_path, branch_name, options, no_cd);
+    }
 
     Ok(())
 }
 
+/// Fall back for `--create`: check out an existing local branch, or branch from the default branch
+fn create_local_branch_worktree(branch: &str, worktree_path: &Path, git_root: &Path) -> Result<()> {
+    if git::local_branch_exists(branch, git_root)? {
+        eprintln!("Using local branch '{}'", branch);
+        return git::worktree_add_at_ref(worktree_path, branch);
+    }
+
+    let default_branch = git::get_default_branch(git_root)?;
+    eprintln!("Creating branch '{}' from '{}'", branch, default_branch);
+    git::worktree_add_with_branch(worktree_path, branch, &default_branch)
+}
+
 /// Parse branch name, stripping `origin/` prefix if present
 fn parse_branch_name(remote_branch: &str) -> &str {
     remote_branch.strip_prefix("origin/").unwrap_or(remote_branch)
@@ -0,0 +1,286 @@
+use anyhow::{bail, Context, Result};
+use std::env;
+use std::fs;
+use std::process::Command;
+
+use crate::cmd::init::CONFIG_TEMPLATE;
+use crate::config::{Config, RepoConfig};
+use crate::git;
+
+/// Execute the `gj config set` command
+pub fn set(key: &str, value: &str, force: bool) -> Result<()> {
+    let config_path = Config::config_path()?;
+    let mut config = Config::load_required()?;
+
+    // Re-serializing the config drops comments, so make the user opt in
+    let content = fs::read_to_string(&config_path)
+        .with_context(|| format!("Failed to read config file: {}", config_path.display()))?;
+    if has_comments(&content) && !force {
+        bail!(
+            "Configuration file {} contains comments, which will be lost when it is rewritten.\n\n\
+            Use `gj config set --force {} {}` to proceed anyway.",
+            config_path.display(),
+            key,
+            value
+        );
+    }
+
+    set_value(&mut config, key, value)?;
+    config.save()?;
+
+    eprintln!("Set {} = {}", key, value);
+
+    Ok(())
+}
+
+/// Execute the `gj config edit` command
+pub fn edit() -> Result<()> {
+    let config_path = Config::config_path()?;
+
+    if !config_path.exists() {
+        fs::create_dir_all(Config::config_dir()?)?;
+        fs::write(&config_path, CONFIG_TEMPLATE)?;
+        eprintln!("Created configuration file at {}", config_path.display());
+    }
+
+    // A config that no longer parses is exactly what the user may be here to fix
+    let configured = Config::load().ok().and_then(|config| config.default.editor);
+    let editor = resolve_editor(
+        configured,
+        env::var("VISUAL").ok(),
+        env::var("EDITOR").ok(),
+    );
+
+    // Run through the shell so editors with arguments (e.g. `code --wait`) work
+    let status = git::traced(
+        Command::new("sh")
+            .arg("-c")
+            .arg(format!("{} \"$1\"", editor))
+            .arg("sh")
+            .arg(&config_path),
+    )
+    .status()
+    .with_context(|| format!("Failed to start editor: {}", editor))?;
+
+    if !status.success() {
+        bail!("Editor '{}' exited with {}", editor, status);
+    }
+
+    if let Err(e) = Config::load() {
+        eprintln!("Warning: {:#}", e);
+        eprintln!("Run `gj config edit` again to fix it.");
+    }
+
+    Ok(())
+}
+
+/// Pick the editor: `default.editor`, then $VISUAL, then $EDITOR, then vi
+fn resolve_editor(
+    configured: Option<String>,
+    visual: Option<String>,
+    editor: Option<String>,
+) -> String {
+    [configured, visual, editor]
+        .into_iter()
+        .flatten()
+        .find(|e| !e.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string())
+}
+
+/// Set a dotted key (e.g., `default.prefix`, `repos.my-app.base_dir`) on the config
+fn set_value(config: &mut Config, key: &str, value: &str) -> Result<()> {
+    if let Some(field) = key.strip_prefix("default.") {
+        match field {
+            "base_dir" => config.default.base_dir = Some(value.to_string()),
+            "prefix" => config.default.prefix = Some(value.to_string()),
+            "auto_prune" => config.default.auto_prune = parse_bool(key, value)?,
+            "git_path" => config.default.git_path = Some(value.to_string()),
+            "archive_dir" => config.default.archive_dir = Some(value.to_string()),
+            "parallel_hooks" => config.default.parallel_hooks = parse_bool(key, value)?,
+            "pr_comment" => config.default.pr_comment = Some(value.to_string()),
+            "flat_layout" => config.default.flat_layout = parse_bool(key, value)?,
+            "editor" => config.default.editor = Some(value.to_string()),
+            "no_cd" => config.default.no_cd = parse_bool(key, value)?,
+            "slugify" => config.default.slugify = parse_bool(key, value)?,
+            "copy_gitignored" => config.default.copy_gitignored = parse_bool(key, value)?,
+            "allow_local_hooks" => config.default.allow_local_hooks = parse_bool(key, value)?,
+            "stale_after" => {
+                crate::config::parse_duration(value)?;
+                config.default.stale_after = Some(value.to_string());
+            }
+            "warn_stale_after" => {
+                if value != "off" {
+                    crate::config::parse_duration(value)?;
+                }
+                config.default.warn_stale_after = Some(value.to_string());
+            }
+            _ => bail!("Unknown config key: {}", key),
+        }
+        return Ok(());
+    }
+
+    if let Some(rest) = key.strip_prefix("repos.") {
+        let (name, field) = rest
+            .rsplit_once('.')
+            .with_context(|| format!("Invalid config key: {}", key))?;
+        if name.is_empty() {
+            bail!("Invalid config key: {}", key);
+        }
+
+        if !config.repos.contains_key(name) {
+            if field != "path" {
+                bail!(
+                    "Repository '{}' is not configured. Set `repos.{}.path` first.",
+                    name,
+                    name
+                );
+            }
+            config.repos.insert(
+                name.to_string(),
+                RepoConfig {
+                    path: value.to_string(),
+                    base_dir: None,
+                    prefix: None,
+                    default_base: None,
+                    seed_branch: None,
+                    no_cd: None,
+                    hook_env: Default::default(),
+                    hooks: Default::default(),
+                },
+            );
+            return Ok(());
+        }
+
+        let repo = config.repos.get_mut(name).unwrap();
+        match field {
+            "path" => repo.path = value.to_string(),
+            "base_dir" => repo.base_dir = Some(value.to_string()),
+            "prefix" => repo.prefix = Some(value.to_string()),
+            "default_base" => repo.default_base = Some(value.to_string()),
+            "seed_branch" => repo.seed_branch = Some(value.to_string()),
+            "no_cd" => repo.no_cd = Some(parse_bool(key, value)?),
+            _ => bail!("Unknown config key: {}", key),
+        }
+        return Ok(());
+    }
+
+    bail!(
+        "Unknown config key: {}. Keys must start with `default.` or `repos.<name>.`",
+        key
+    )
+}
+
+/// Parse a boolean config value
+fn parse_bool(key: &str, value: &str) -> Result<bool> {
+    value
+        .parse()
+        .with_context(|| format!("Invalid value for {}: expected true or false", key))
+}
+
+/// Whether the TOML content contains any comment lines
+fn has_comments(content: &str) -> bool {
+    content
+        .lines()
+        .any(|line| line.trim_start().starts_with('#'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_value_default_keys() {
+        let mut config = Config::default();
+        set_value(&mut config, "default.prefix", "feature").unwrap();
+        set_value(&mut config, "default.base_dir", "~/code/wt").unwrap();
+
+        assert_eq!(config.default.prefix, Some("feature".to_string()));
+        assert_eq!(config.default.base_dir, Some("~/code/wt".to_string()));
+
+        set_value(&mut config, "default.auto_prune", "true").unwrap();
+        assert!(config.default.auto_prune);
+        assert!(set_value(&mut config, "default.auto_prune", "yes").is_err());
+
+        set_value(&mut config, "default.warn_stale_after", "off").unwrap();
+        assert_eq!(config.get_warn_stale_after().unwrap(), None);
+        set_value(&mut config, "default.warn_stale_after", "14d").unwrap();
+        assert_eq!(
+            config.get_warn_stale_after().unwrap(),
+            Some(chrono::Duration::days(14))
+        );
+        assert!(set_value(&mut config, "default.warn_stale_after", "soon").is_err());
+    }
+
+    #[test]
+    fn test_set_value_repo_keys() {
+        let mut config = Config::default();
+        set_value(&mut config, "repos.my.app.path", "~/dev/my.app").unwrap();
+        set_value(&mut config, "repos.my.app.prefix", "contrib").unwrap();
+        set_value(&mut config, "repos.my.app.default_base", "origin/develop").unwrap();
+        set_value(&mut config, "repos.my.app.seed_branch", "scaffold").unwrap();
+
+        let repo = config.repos.get("my.app").unwrap();
+        assert_eq!(repo.path, "~/dev/my.app");
+        assert_eq!(repo.prefix, Some("contrib".to_string()));
+        assert_eq!(repo.default_base, Some("origin/develop".to_string()));
+        assert_eq!(repo.seed_branch, Some("scaffold".to_string()));
+    }
+
+    #[test]
+    fn test_set_value_unknown_repo_requires_path() {
+        let mut config = Config::default();
+        let result = set_value(&mut config, "repos.missing.prefix", "x");
+        assert!(result.is_err());
+        assert!(config.repos.is_empty());
+    }
+
+    #[test]
+    fn test_set_value_unknown_key() {
+        let mut config = Config::default();
+        assert!(set_value(&mut config, "default.unknown", "x").is_err());
+        assert!(set_value(&mut config, "prefix", "x").is_err());
+    }
+
+    #[test]
+    fn test_round_trip_preserves_unrelated_sections() {
+        let mut config: Config = toml::from_str(
+            r#"
+[default]
+prefix = "gj"
+
+[[default.hooks.post_create]]
+type = "run"
+command = "echo hi"
+
+[repos.other]
+path = "/path/other"
+"#,
+        )
+        .unwrap();
+
+        set_value(&mut config, "default.prefix", "feature").unwrap();
+
+        let serialized = toml::to_string_pretty(&config).unwrap();
+        let reloaded: Config = toml::from_str(&serialized).unwrap();
+
+        assert_eq!(reloaded.default.prefix, Some("feature".to_string()));
+        assert_eq!(reloaded.default.hooks.post_create.len(), 1);
+        assert_eq!(reloaded.repos.get("other").unwrap().path, "/path/other");
+    }
+
+    #[test]
+    fn test_resolve_editor() {
+        let s = |v: &str| Some(v.to_string());
+        assert_eq!(resolve_editor(s("hx"), s("code -w"), s("nano")), "hx");
+        assert_eq!(resolve_editor(None, s("code -w"), s("nano")), "code -w");
+        assert_eq!(resolve_editor(None, s(""), s("nano")), "nano");
+        assert_eq!(resolve_editor(None, None, None), "vi");
+    }
+
+    #[test]
+    fn test_has_comments() {
+        assert!(has_comments("# comment\n[default]\n"));
+        assert!(has_comments("[default]\n  # indented\n"));
+        assert!(!has_comments("[default]\nprefix = \"gj\"\n"));
+    }
+}
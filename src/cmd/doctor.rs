@@ -0,0 +1,204 @@
+use anyhow::{bail, Result};
+use serde::Serialize;
+
+use crate::config::{Config, JSON_CONFIG_FILE};
+use crate::git;
+use crate::state;
+
+/// Result status of a single diagnostic check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum CheckStatus {
+    Ok,
+    Warn,
+    Fail,
+}
+
+/// A single diagnostic check result
+#[derive(Debug, Clone, Serialize)]
+struct Check {
+    name: &'static str,
+    status: CheckStatus,
+    detail: String,
+}
+
+impl Check {
+    fn new(name: &'static str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Check {
+            name,
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Execute the `gj doctor` command
+pub fn run(json: bool) -> Result<()> {
+    let checks = vec![check_git(), check_gh(), check_config(), check_state()];
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&checks)?);
+    } else {
+        for check in &checks {
+            println!(
+                "{:<6} {:<8} {}",
+                status_label(check.status),
+                check.name,
+                check.detail
+            );
+        }
+    }
+
+    if has_critical_failure(&checks) {
+        bail!("One or more critical checks failed");
+    }
+
+    Ok(())
+}
+
+/// Check that git is installed
+fn check_git() -> Check {
+    let version = match git::version() {
+        Ok(version) => version,
+        Err(e) => return Check::new("git", CheckStatus::Fail, e.to_string()),
+    };
+
+    match git::parse_git_version(&version) {
+        Some(parsed) if parsed < git::MIN_GIT_VERSION => Check::new(
+            "git",
+            CheckStatus::Warn,
+            format!(
+                "{} is older than the minimum supported version {}.{}",
+                version,
+                git::MIN_GIT_VERSION.0,
+                git::MIN_GIT_VERSION.1
+            ),
+        ),
+        _ => Check::new("git", CheckStatus::Ok, version),
+    }
+}
+
+/// Check that gh CLI is installed (only required for `gj pr`)
+fn check_gh() -> Check {
+    if git::is_gh_available() {
+        Check::new("gh", CheckStatus::Ok, "gh CLI is available")
+    } else {
+        Check::new(
+            "gh",
+            CheckStatus::Warn,
+            "gh CLI is not installed (required for `gj pr`)",
+        )
+    }
+}
+
+/// Check that the configuration file exists and parses
+fn check_config() -> Check {
+    let config_path = match Config::config_path() {
+        Ok(path) => path,
+        Err(e) => return Check::new("config", CheckStatus::Fail, e.to_string()),
+    };
+
+    if !config_path.exists() {
+        return Check::new(
+            "config",
+            CheckStatus::Fail,
+            format!(
+                "Configuration file not found at {}. Run `gj init`.",
+                state::display_path(&config_path)
+            ),
+        );
+    }
+
+    let json_path = config_path.with_file_name(JSON_CONFIG_FILE);
+    match Config::load() {
+        Ok(_) if json_path != config_path && json_path.exists() => Check::new(
+            "config",
+            CheckStatus::Warn,
+            format!(
+                "{} is ignored because {} exists",
+                state::display_path(&json_path),
+                state::display_path(&config_path)
+            ),
+        ),
+        Ok(_) => Check::new("config", CheckStatus::Ok, state::display_path(&config_path)),
+        Err(e) => Check::new("config", CheckStatus::Fail, format!("{:#}", e)),
+    }
+}
+
+/// Check that state files are readable and point to existing worktrees
+fn check_state() -> Check {
+    let states = match state::list_all_states() {
+        Ok(states) => states,
+        Err(e) => return Check::new("state", CheckStatus::Fail, format!("{:#}", e)),
+    };
+
+    let missing = states.iter().filter(|s| !s.worktree_path.exists()).count();
+
+    if missing > 0 {
+        Check::new(
+            "state",
+            CheckStatus::Warn,
+            format!(
+                "{} of {} managed worktrees no longer exist on disk",
+                missing,
+                states.len()
+            ),
+        )
+    } else {
+        Check::new(
+            "state",
+            CheckStatus::Ok,
+            format!("{} managed worktrees", states.len()),
+        )
+    }
+}
+
+/// Human-readable label for a check status
+fn status_label(status: CheckStatus) -> &'static str {
+    match status {
+        CheckStatus::Ok => "[ok]",
+        CheckStatus::Warn => "[warn]",
+        CheckStatus::Fail => "[fail]",
+    }
+}
+
+/// Whether any check failed critically
+fn has_critical_failure(checks: &[Check]) -> bool {
+    checks.iter().any(|c| c.status == CheckStatus::Fail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_serializes_to_json() {
+        let check = Check::new("git", CheckStatus::Ok, "git version 2.43.0");
+        let json = serde_json::to_value(&check).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "name": "git",
+                "status": "ok",
+                "detail": "git version 2.43.0"
+            })
+        );
+    }
+
+    #[test]
+    fn test_has_critical_failure() {
+        let ok = Check::new("git", CheckStatus::Ok, "");
+        let warn = Check::new("gh", CheckStatus::Warn, "");
+        let fail = Check::new("config", CheckStatus::Fail, "");
+
+        assert!(!has_critical_failure(&[ok.clone(), warn.clone()]));
+        assert!(has_critical_failure(&[ok, warn, fail]));
+    }
+
+    #[test]
+    fn test_status_label() {
+        assert_eq!(status_label(CheckStatus::Ok), "[ok]");
+        assert_eq!(status_label(CheckStatus::Warn), "[warn]");
+        assert_eq!(status_label(CheckStatus::Fail), "[fail]");
+    }
+}
@@ -1,24 +1,269 @@
 use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::env;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
 
+use super::list;
+use crate::config::Config;
+use crate::error::GjError;
 use crate::git;
-use crate::state::WorktreeState;
+use crate::history::{self, EventKind};
+use crate::hooks;
+use crate::state::{self, WorktreeState};
+
+/// Arguments for the `gj exit` command
+#[derive(Debug, Clone, clap::Args)]
+pub struct ExitArgs {
+    /// Worktree names (defaults to the current worktree); several are exited one by one
+    #[arg(value_name = "NAME")]
+    pub names: Vec<String>,
+    /// Exit every unpinned worktree of this repository whose branch is merged into the default
+    #[arg(long, conflicts_with = "names")]
+    pub all_merged: bool,
+    /// Force removal even with uncommitted changes
+    #[arg(long, short)]
+    pub force: bool,
+    /// Merge the worktree branch into the default branch before exiting
+    #[arg(long, short)]
+    pub merge: bool,
+    /// Commit uncommitted changes before merging (prompts if no message is given)
+    #[arg(long, requires = "merge", num_args = 0..=1, default_missing_value = "")]
+    pub commit: Option<String>,
+    /// Print key=value lines (path, merge_commit) instead of the bare path
+    #[arg(long)]
+    pub porcelain: bool,
+    /// Archive the worktree as .tar.gz before removing it
+    #[arg(long)]
+    pub archive: bool,
+    /// Rebase onto the default branch, then fast-forward merge
+    #[arg(long, requires = "merge")]
+    pub rebase: bool,
+    /// Stash uncommitted changes (as `gj:<branch>: ...`) instead of refusing or discarding them
+    #[arg(long, conflicts_with = "merge")]
+    pub stash: bool,
+    /// Merge, but keep the worktree, branch and state (and stay where you are)
+    #[arg(long, requires = "merge", conflicts_with = "archive")]
+    pub no_delete_worktree: bool,
+    /// Don't prompt: a conflicting --merge is aborted
+    #[arg(long, short)]
+    pub yes: bool,
+}
+
+/// Where an exit leaves the user
+struct Landing {
+    path: PathBuf,
+    /// HEAD of the default branch after merging, if --merge was used
+    merge_commit: Option<String>,
+    /// Commit of the stash holding the worktree's changes, if --stash stashed any
+    stash: Option<String>,
+    /// Whether the shell wrapper should change into `path` (otherwise it is only
+    /// reported with --porcelain)
+    cd: bool,
+}
 
 /// Execute the `gj exit` command
-pub fn run(force: bool, merge: bool) -> Result<()> {
-    // Load state for current directory
-    let state = WorktreeState::load_current()?.context(
-        "Not in a gj-managed worktree. Use this command inside a worktree created by gj.",
-    )?;
+pub fn run(args: ExitArgs) -> Result<()> {
+    if args.all_merged || args.names.len() > 1 {
+        return run_batch(args);
+    }
+
+    // Load state for the named worktree, or the current directory
+    let state = match args.names.first() {
+        Some(name) => super::cd::find_worktree(name)?,
+        None => WorktreeState::load_current()?.ok_or(GjError::NotInWorktree)?,
+    };
+    let landing = exit_worktree(state, &args, !args.porcelain)?;
+    print_landing(&landing, args.porcelain);
+    Ok(())
+}
+
+/// Exit several worktrees, continuing past failures. Dirty worktrees are skipped unless
+/// --force (or --stash / --commit keep their changes); conflicting merges are aborted and
+/// after_exit hooks run directly. The shell only moves if its directory was removed.
+fn run_batch(args: ExitArgs) -> Result<()> {
+    let targets: Vec<(String, Result<WorktreeState>)> = if args.all_merged {
+        merged_states()?
+            .into_iter()
+            .map(|s| (state::display_path(&s.worktree_path), Ok(s)))
+            .collect()
+    } else {
+        args.names
+            .iter()
+            .map(|name| (name.clone(), super::cd::find_worktree(name)))
+            .collect()
+    };
+    if targets.is_empty() {
+        eprintln!("No merged worktrees to exit");
+        return Ok(());
+    }
+
+    let args = ExitArgs { yes: true, ..args };
+    let keeps_changes = args.force || args.stash || args.commit.is_some();
+    let current_dir = env::current_dir().ok();
+    let mut landing = None;
+    let (mut exited, mut skipped) = (0, 0);
+    let mut failed = Vec::new();
+
+    for (label, target) in targets {
+        let result = target.and_then(|state| {
+            if !keeps_changes && git::has_uncommitted_changes(&state.worktree_path)? {
+                eprintln!(
+                    "Warning: Skipping {}: uncommitted changes (use --force to discard them)",
+                    label
+                );
+                skipped += 1;
+                return Ok(());
+            }
+            let inside = current_dir
+                .as_deref()
+                .is_some_and(|dir| list::is_within(dir, &state.worktree_path));
+            let exit = exit_worktree(state, &args, false)?;
+            exited += 1;
+            if inside {
+                landing = Some(exit);
+            }
+            Ok(())
+        });
+        if let Err(e) = result {
+            eprintln!("Warning: Failed to exit {}: {:#}", label, e);
+            failed.push(label);
+        }
+    }
+
+    eprintln!(
+        "Exited {} worktree(s); skipped {}, failed {}",
+        exited,
+        skipped,
+        failed.len()
+    );
+    if !failed.is_empty() {
+        let moved = landing.map_or(String::new(), |l| {
+            format!("\nYour directory was removed; run `cd {}`.", l.path.display())
+        });
+        bail!("Failed to exit: {}{}", failed.join(", "), moved);
+    }
+    if let Some(landing) = landing {
+        print_landing(&landing, args.porcelain);
+    }
+    Ok(())
+}
+
+/// Unpinned gj worktrees of the current repository whose branch is merged into its
+/// default branch
+fn merged_states() -> Result<Vec<WorktreeState>> {
+    let origin_repo = list::current_origin_repo().ok_or(GjError::NotInRepo)?;
+    let mut merged_cache = HashMap::new();
+
+    Ok(state::list_all_states()?
+        .into_iter()
+        .filter(|s| list::in_scope(s, Some(&origin_repo)) && s.worktree_path.exists())
+        .filter(|s| !s.pinned)
+        .filter(|s| list::merged_into_default(&mut merged_cache, &s.origin_repo, &s.branch))
+        .collect())
+}
+
+/// Print where the exit leaves the user for the shell wrapper (or key=value lines)
+fn print_landing(landing: &Landing, porcelain: bool) {
+    if porcelain {
+        let mut fields = vec![("path", landing.path.display().to_string())];
+        if let Some(sha) = &landing.merge_commit {
+            fields.push(("merge_commit", sha.clone()));
+        }
+        if let Some(sha) = &landing.stash {
+            fields.push(("stash", sha.clone()));
+        }
+        print!("{}", super::format_porcelain(&fields));
+    } else if landing.cd {
+        println!("{}", landing.path.display());
+    }
+}
+
+/// Run the exit flow for one worktree. With `use_wrapper`, after_exit hooks are handed to
+/// the shell wrapper.
+fn exit_worktree(state: WorktreeState, args: &ExitArgs, use_wrapper: bool) -> Result<Landing> {
+    let ExitArgs {
+        force,
+        merge,
+        archive,
+        rebase,
+        stash,
+        no_delete_worktree,
+        yes,
+        ..
+    } = *args;
+
+    if !state.origin_repo.is_dir() {
+        return Err(GjError::OriginMissing(state.origin_repo).into());
+    }
+
+    let config = Config::load()?;
+    let repo_config = config.resolve_repo(&state.origin_repo)?;
+    let hook_env = config.get_hook_env(repo_config.as_ref());
+
+    // Removing the worktree would throw away a half-finished rebase or merge
+    if !force {
+        if let Some(operation) = git::in_progress_operation(&state.worktree_path)? {
+            bail!(
+                "A {} is in progress in this worktree and would be lost.\n\
+                Finish it (`git {} --continue`) or abort it (`git {} --abort`), \
+                or use --force to exit anyway.",
+                operation,
+                operation,
+                operation
+            );
+        }
+    }
+
+    // Let pre_remove hooks veto the exit before anything is changed
+    let pre_remove = if no_delete_worktree {
+        Vec::new()
+    } else {
+        config.get_pre_remove_hooks(repo_config.as_ref())
+    };
+    let vetoed = hooks::execute_hooks(
+        &pre_remove,
+        &state.origin_repo,
+        &state.worktree_path,
+        &hook_env,
+        false,
+    );
+    if let Err(e) = vetoed {
+        if !force {
+            bail!(
+                "pre_remove hook failed, not exiting: {:#}\nUse --force to exit anyway.",
+                e
+            );
+        }
+        eprintln!("Warning: pre_remove hook failed: {:#}", e);
+    }
+
+    // Commit of the stash holding uncommitted changes, if --stash was used
+    let mut stashed = None;
 
     // Check for uncommitted changes unless --force
-    // For --merge, we always require clean state
-    if merge && git::has_uncommitted_changes()? {
-        bail!(
-            "Worktree has uncommitted changes. Commit or stash them before using --merge."
-        );
-    } else if !force && !merge && git::has_uncommitted_changes()? {
+    // For --merge, we require clean state unless --commit is given
+    if merge && git::has_uncommitted_changes(&state.worktree_path)? {
+        match args.commit.as_deref() {
+            Some(message) => {
+                let message = match message.trim() {
+                    "" => prompt_commit_message()?,
+                    message => message.to_string(),
+                };
+                git::commit_all(&message, &state.worktree_path)?;
+                eprintln!("Committed changes: {}", message);
+            }
+            None => bail!(
+                "Worktree has uncommitted changes. Commit or stash them before using --merge, or use --commit."
+            ),
+        }
+    } else if stash && git::has_uncommitted_changes(&state.worktree_path)? {
+        stashed = Some(stash_changes(&state)?);
+    } else if !force && !merge && git::has_uncommitted_changes(&state.worktree_path)? {
         bail!(
-            "Worktree has uncommitted changes. Use --force to discard them, or commit/stash first."
+            "Worktree has uncommitted changes. Use --force to discard them, --stash to keep them \
+            in the stash, or commit/stash first."
         );
     }
 
@@ -27,6 +272,9 @@ pub fn run(force: bool, merge: bool) -> Result<()> {
     let branch = state.branch.clone();
     let worktree_path = state.worktree_path.clone();
 
+    // HEAD of the default branch after merging, if --merge was used
+    let mut merge_commit = None;
+
     // Handle merge if requested
     let target_dir = if merge {
         // Get the default branch
@@ -39,8 +287,50 @@ pub fn run(force: bool, merge: bool) -> Result<()> {
                 default_branch
             ))?;
 
+        // Rebase the worktree branch onto the default branch so the merge fast-forwards
+        if rebase {
+            if let Err(e) = git::rebase_onto(&default_branch, &worktree_path) {
+                // Rebase failed, abort and return error
+                let _ = git::rebase_abort(&worktree_path);
+                bail!(
+                    "Rebase failed. Conflict detected. Aborting rebase.\nError: {}",
+                    e
+                );
+            }
+            eprintln!("Rebased '{}' onto '{}'", branch, default_branch);
+        }
+
         // Merge the worktree branch in the target worktree
-        if let Err(e) = git::merge_branch(&branch, &merge_worktree) {
+        let merged = if rebase {
+            git::merge_branch_ff_only(&branch, &merge_worktree)
+        } else {
+            git::merge_branch(&branch, &merge_worktree)
+        };
+        if let Err(e) = merged {
+            // Leave a conflicted merge in place if the user wants to resolve it by hand
+            let conflicted = matches!(
+                git::in_progress_operation(&merge_worktree),
+                Ok(Some("merge"))
+            );
+            if conflicted && !yes && confirm_keep_conflict(&branch, &default_branch) {
+                eprintln!(
+                    "Kept the conflicted merge in {}",
+                    crate::state::display_path(&merge_worktree)
+                );
+                eprintln!(
+                    "Resolve the conflicts and commit there, then run `gj exit {}` \
+                    to remove the worktree.",
+                    branch
+                );
+                // The shell wrapper takes the user to the conflicts
+                return Ok(Landing {
+                    path: merge_worktree,
+                    merge_commit: None,
+                    stash: stashed,
+                    cd: true,
+                });
+            }
+
             // Merge failed, abort and return error
             let _ = git::merge_abort(&merge_worktree);
             bail!(
@@ -50,25 +340,164 @@ pub fn run(force: bool, merge: bool) -> Result<()> {
         }
 
         eprintln!("Merged '{}' into '{}'", branch, default_branch);
+        merge_commit = Some(git::rev_parse("HEAD", &merge_worktree)?);
         merge_worktree
     } else {
         origin_repo.clone()
     };
 
-    // Remove the worktree (run from origin repo)
-    git::worktree_remove(&worktree_path, force, &origin_repo)?;
+    // Merge up without tearing down: nothing is removed and the shell stays put
+    if no_delete_worktree {
+        eprintln!("Kept worktree: {}", crate::state::display_path(&worktree_path));
+        return Ok(Landing {
+            path: worktree_path,
+            merge_commit,
+            stash: stashed,
+            cd: false,
+        });
+    }
+
+    // Snapshot the worktree before it is removed
+    if archive {
+        super::archive::archive_worktree(&state, &config, false)?;
+    }
 
-    // Delete the branch (run from origin repo)
     // When merging, the branch is already merged so we can safely delete it
-    git::branch_delete(&branch, force || merge, &origin_repo)?;
-
-    // Delete the state file
-    state.delete()?;
+    remove_worktree(&state, force, force || merge)?;
 
     // Output status message and target directory path
     eprintln!("Removed worktree: {}", crate::state::display_path(&worktree_path));
     eprintln!("Deleted branch: {}", branch);
-    println!("{}", target_dir.display());
+
+    // Run post_last_exit hooks in the origin repo once its last gj worktree is gone
+    let post_last_exit = config.get_post_last_exit_hooks(repo_config.as_ref());
+    if !post_last_exit.is_empty() && is_last_worktree(&origin_repo, &worktree_path)? {
+        let result =
+            hooks::execute_hooks(&post_last_exit, &origin_repo, &origin_repo, &hook_env, false);
+        if let Err(e) = result {
+            eprintln!("Warning: post_last_exit hook failed: {}", e);
+        }
+    }
+
+    // Run after_exit hooks in the returned-to directory (through the shell wrapper if active)
+    let after_exit = config.get_after_exit_hooks(repo_config.as_ref());
+    let result = hooks::execute_after_exit_hooks(&after_exit, &target_dir, &hook_env, use_wrapper);
+    if let Err(e) = result {
+        eprintln!("Warning: after_exit hook failed: {}", e);
+    }
+
+    Ok(Landing {
+        path: target_dir,
+        merge_commit,
+        stash: stashed,
+        cd: true,
+    })
+}
+
+/// Stash a worktree's changes (including untracked files) where they outlive the worktree,
+/// and print how to get them back. Returns the stash commit.
+fn stash_changes(state: &WorktreeState) -> Result<String> {
+    let message = stash_message(&state.branch, Utc::now());
+    git::stash_push(&message, &state.worktree_path)?;
+
+    // The stash is shared by all worktrees, so it survives removing this one
+    let sha = git::rev_parse("refs/stash", &state.worktree_path)?;
+    eprintln!("Stashed uncommitted changes as stash@{{0}} ({}): {}", sha, message);
+    eprintln!(
+        "Recover them with `git stash branch {} {}` in {}",
+        state.branch,
+        sha,
+        crate::state::display_path(&state.origin_repo)
+    );
+    Ok(sha)
+}
+
+/// Stash message that `git stash list` can be searched by: `gj:<branch>: ...`
+fn stash_message(branch: &str, now: DateTime<Utc>) -> String {
+    format!(
+        "gj:{}: uncommitted changes at gj exit ({})",
+        branch,
+        now.format("%Y-%m-%d %H:%M")
+    )
+}
+
+/// Remove a worktree, clear its state and delete its branch (git runs from the origin repo).
+/// `force` discards uncommitted changes; `force_branch` deletes an unmerged branch.
+pub fn remove_worktree(state: &WorktreeState, force: bool, force_branch: bool) -> Result<()> {
+    git::worktree_remove(&state.worktree_path, force, &state.origin_repo)?;
+    history::record(EventKind::Exit, state);
+
+    // The worktree is already gone, so from here on failures only warn. The state goes
+    // first so that a branch that cannot be deleted does not leave it behind.
+    if let Err(e) = state.delete() {
+        eprintln!(
+            "Warning: Worktree was removed, but its state could not be cleared: {:#}",
+            e
+        );
+        eprintln!("Run `gj list --prune` to clean up stale state.");
+    }
+
+    if let Err(e) = git::branch_delete(&state.branch, force_branch, &state.origin_repo) {
+        eprintln!(
+            "Warning: Worktree was removed, but its branch {} could not be deleted: {:#}",
+            state.branch, e
+        );
+    }
 
     Ok(())
 }
+
+/// Whether no gj worktree other than `removed` remains for `origin_repo`
+fn is_last_worktree(origin_repo: &Path, removed: &Path) -> Result<bool> {
+    let remaining = crate::state::list_all_states()?
+        .into_iter()
+        .any(|s| s.origin_repo == origin_repo && s.worktree_path != removed);
+    Ok(!remaining)
+}
+
+/// Ask whether to keep a conflicted merge for manual resolution; defaults to aborting,
+/// and never keeps it without a terminal to ask on. The merge has started already, so a
+/// cancelled or failed prompt also means aborting rather than leaving it half done.
+fn confirm_keep_conflict(branch: &str, default_branch: &str) -> bool {
+    if !std::io::stdin().is_terminal() {
+        return false;
+    }
+
+    inquire::Confirm::new(&format!(
+        "Merging '{}' into '{}' conflicts. Keep the conflicts to resolve them manually?",
+        branch, default_branch
+    ))
+    .with_default(false)
+    .with_help_message("No aborts the merge and leaves the worktree as it was")
+    .prompt()
+    .unwrap_or(false)
+}
+
+/// Prompt the user for a commit message
+fn prompt_commit_message() -> Result<String> {
+    let message = inquire::Text::new("Enter commit message:")
+        .prompt()
+        .context("Failed to get commit message input")?;
+
+    let message = message.trim().to_string();
+    if message.is_empty() {
+        bail!("Commit message cannot be empty");
+    }
+
+    Ok(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_stash_message() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 15, 9, 30, 0).unwrap();
+        assert_eq!(
+            stash_message("gj/20261015_fix", now),
+            "gj:gj/20261015_fix: uncommitted changes at gj exit (2026-10-15 09:30)"
+        );
+    }
+}
@@ -0,0 +1,207 @@
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+
+use crate::config::{Config, Hook, HooksConfig, LocalConfig, RepoConfig};
+
+/// Selects one stage's hooks from a hooks table
+type StageHooks = fn(&HooksConfig) -> &Vec<Hook>;
+
+/// Hook stages in the order gj runs them over a worktree's life
+const STAGES: [(&str, StageHooks); 4] = [
+    ("post_create", |hooks| &hooks.post_create),
+    ("pre_remove", |hooks| &hooks.pre_remove),
+    ("post_last_exit", |hooks| &hooks.post_last_exit),
+    ("after_exit", |hooks| &hooks.after_exit),
+];
+
+/// Where hooks are configured, in the order their hooks run
+struct Source<'a> {
+    label: String,
+    hooks: &'a HooksConfig,
+    hook_env: BTreeMap<&'a String, &'a String>,
+}
+
+/// Execute the `gj hooks` command: print the hooks gj would run for the current
+/// repository (or --repo), labeled with where each is configured
+pub fn run(repo: Option<String>) -> Result<()> {
+    let config = Config::load()?;
+
+    let repo_root = match &repo {
+        Some(name) => {
+            let repo_config = config.repos.get(name).with_context(|| {
+                format!("Repository '{}' is not configured in the config file", name)
+            })?;
+            Some(repo_config.expanded_path())
+        }
+        None => super::list::current_origin_repo(),
+    };
+
+    let registered = repo_root.as_ref().and_then(|root| config.find_repo(root));
+    let local = match &repo_root {
+        Some(root) => config.load_local(root)?,
+        None => None,
+    };
+
+    print!(
+        "{}",
+        format_plan(&sources(&config, registered, local.as_ref()))
+    );
+    Ok(())
+}
+
+/// The default hooks, then the registered repository's, then `.gj.toml`'s
+fn sources<'a>(
+    config: &'a Config,
+    registered: Option<(&String, &'a RepoConfig)>,
+    local: Option<&'a LocalConfig>,
+) -> Vec<Source<'a>> {
+    let mut sources = vec![Source {
+        label: "default".to_string(),
+        hooks: &config.default.hooks,
+        hook_env: config.default.hook_env.iter().collect(),
+    }];
+    if let Some((name, repo)) = registered {
+        sources.push(Source {
+            label: format!("repos.{}", name),
+            hooks: &repo.hooks,
+            hook_env: repo.hook_env.iter().collect(),
+        });
+    }
+    if let Some(local) = local {
+        sources.push(Source {
+            label: LocalConfig::FILE_NAME.to_string(),
+            hooks: &local.hooks,
+            hook_env: local.hook_env.iter().collect(),
+        });
+    }
+    sources
+}
+
+/// Format every stage's hooks in execution order, then the hook environment
+fn format_plan(sources: &[Source]) -> String {
+    let mut output = String::new();
+
+    for (stage, hooks_of) in STAGES {
+        let hooks: Vec<(&str, &Hook)> = sources
+            .iter()
+            .flat_map(|source| {
+                hooks_of(source.hooks)
+                    .iter()
+                    .map(|hook| (source.label.as_str(), hook))
+            })
+            .collect();
+
+        if hooks.is_empty() {
+            output.push_str(&format!("{}: (none)\n", stage));
+            continue;
+        }
+        output.push_str(&format!("{}:\n", stage));
+        for (i, (label, hook)) in hooks.iter().enumerate() {
+            output.push_str(&format!("  {}. [{}] {}\n", i + 1, label, describe(hook)));
+        }
+    }
+
+    // Later sources override earlier ones
+    let mut hook_env = BTreeMap::new();
+    for source in sources {
+        for (key, value) in &source.hook_env {
+            hook_env.insert(*key, (*value, source.label.as_str()));
+        }
+    }
+    if !hook_env.is_empty() {
+        output.push_str("hook_env:\n");
+        for (key, (value, label)) in hook_env {
+            output.push_str(&format!("  [{}] {}={}\n", label, key, value));
+        }
+    }
+
+    output
+}
+
+/// A hook with all its parameters, e.g. `copy config -> config (required, exclude: *.key)`
+fn describe(hook: &Hook) -> String {
+    let mut details = Vec::new();
+    match hook {
+        Hook::Copy {
+            required, exclude, ..
+        } => {
+            if *required {
+                details.push("required".to_string());
+            }
+            if !exclude.is_empty() {
+                details.push(format!("exclude: {}", exclude.join(", ")));
+            }
+        }
+        Hook::Run { timeout, .. } => {
+            if let Some(timeout) = timeout {
+                details.push(format!("timeout: {}s", timeout));
+            }
+        }
+    }
+
+    if details.is_empty() {
+        hook.to_string()
+    } else {
+        format!("{} ({})", hook, details.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_plan() {
+        let config: Config = toml::from_str(
+            r#"
+[default.hook_env]
+NODE_ENV = "development"
+
+[[default.hooks.post_create]]
+type = "run"
+command = "echo created"
+
+[repos.my-app]
+path = "/path/my-app"
+
+[[repos.my-app.hooks.post_create]]
+type = "copy"
+from = "config"
+required = true
+exclude = ["*.key"]
+
+[[repos.my-app.hooks.after_exit]]
+type = "run"
+command = "git fetch --prune"
+timeout = 60
+"#,
+        )
+        .unwrap();
+        let local: LocalConfig = toml::from_str(
+            r#"
+hook_env = { NODE_ENV = "test" }
+
+[[hooks.post_create]]
+type = "run"
+command = "npm install"
+"#,
+        )
+        .unwrap();
+
+        let registered = config.repos.get_key_value("my-app");
+        let plan = format_plan(&sources(&config, registered, Some(&local)));
+        assert_eq!(
+            plan,
+            "post_create:\n\
+             \x20 1. [default] run echo created\n\
+             \x20 2. [repos.my-app] copy config -> config (required, exclude: *.key)\n\
+             \x20 3. [.gj.toml] run npm install\n\
+             pre_remove: (none)\n\
+             post_last_exit: (none)\n\
+             after_exit:\n\
+             \x20 1. [repos.my-app] run git fetch --prune (timeout: 60s)\n\
+             hook_env:\n\
+             \x20 [.gj.toml] NODE_ENV=test\n"
+        );
+    }
+}
@@ -1,10 +1,10 @@
 use anyhow::{bail, Result};
 use std::fs;
 
-use crate::config::Config;
+use crate::config::{Config, Hook, CONFIG_FILE};
 
 /// Default configuration template with comments
-const CONFIG_TEMPLATE: &str = r#"# gj configuration file
+pub(crate) const CONFIG_TEMPLATE: &str = r#"# gj configuration file
 # See: https://github.com/user/gj for documentation
 
 [default]
@@ -14,6 +14,52 @@ const CONFIG_TEMPLATE: &str = r#"# gj configuration file
 # Default branch prefix (default: gj)
 # prefix = "gj"
 
+# Remove state of worktrees deleted outside gj on `gj list` / `gj cd` (default: false)
+# auto_prune = false
+
+# Path to the git binary (default: git on PATH; git 2.17 or later is required)
+# git_path = "/usr/bin/git"
+
+# Directory for `gj archive` snapshots (default: ~/.gj/archives)
+# archive_dir = "~/.gj/archives"
+
+# Run post_create hooks concurrently and report all failures (default: false)
+# parallel_hooks = false
+
+# Worktrees live at {base_dir}/{host}/{owner}/{repo}/...; set to true to omit the host
+# flat_layout = false
+
+# Comment posted by `gj pr --comment` without text ({user} is your GitHub login)
+# pr_comment = "{user} is reviewing this locally"
+
+# Editor for `gj config edit` (default: $VISUAL, then $EDITOR, then vi)
+# editor = "code --wait"
+
+# Stay in the current directory after gj new/pr/checkout, as with --no-cd (default: false)
+# no_cd = false
+
+# Turn `gj new` names into kebab-case slugs, keeping a leading ticket ID (default: false)
+# "PROJ-123 Some title" -> PROJ-123-some-title
+# slugify = false
+
+# Flag worktrees older than this in `gj list` (m, h, d or w; default: never)
+# stale_after = "14d"
+
+# Note worktrees older than this on any command, at most once a day ("off" or unset: never)
+# warn_stale_after = "30d"
+
+# Copy the origin's ignored, untracked files (e.g. .env) into new worktrees (default: false)
+# copy_gitignored = true
+# copy_gitignored_exclude = ["node_modules", "target"]
+
+# Run the run hooks and use the hook_env of a repository's .gj.toml (default: false).
+# Any repository you clone can ship a .gj.toml, so only enable this if you trust them all.
+# allow_local_hooks = true
+
+# Environment variables for every run hook ($VAR and ~ are expanded)
+# [default.hook_env]
+# NODE_ENV = "development"
+
 # Example: Default hooks applied to all repositories
 # [[default.hooks.post_create]]
 # type = "run"
@@ -23,6 +69,8 @@ const CONFIG_TEMPLATE: &str = r#"# gj configuration file
 # [repos.my-app]
 # path = "~/dev/my-app"
 # prefix = "feature"
+# default_base = "origin/develop"  # Ref that `gj new` branches from (default: HEAD)
+# seed_branch = "scaffold"         # Branch merged into every `gj new` branch (default: none)
 #
 # [[repos.my-app.hooks.post_create]]
 # type = "copy"
@@ -30,31 +78,133 @@ const CONFIG_TEMPLATE: &str = r#"# gj configuration file
 # required = true
 #
 # [[repos.my-app.hooks.post_create]]
+# type = "copy"
+# from = "config"           # Directories are copied recursively
+# exclude = ["secrets.*"]   # Glob patterns to skip inside the directory
+#
+# [[repos.my-app.hooks.post_create]]
 # type = "run"
 # command = "npm install"
+# timeout = 600  # Kill the command after this many seconds (default: no timeout)
+#
+# [[repos.my-app.hooks.pre_remove]]
+# type = "run"
+# command = "git diff --quiet @{upstream}"  # Refuse `gj exit` when this fails (unless --force)
+#
+# [[repos.my-app.hooks.post_last_exit]]
+# type = "run"
+# command = "docker compose down"  # Runs in the origin repo when its last worktree is removed
+#
+# [[repos.my-app.hooks.after_exit]]
+# type = "run"
+# command = "git fetch --prune"  # Runs in the directory `gj exit` returns to
 "#;
 
+/// Repository the `--example` section configures
+const EXAMPLE_REPO: &str = "my-app";
+
+/// Hook stage, description and hook for each example written by `gj init --example`
+fn example_hooks() -> Vec<(&'static str, &'static str, Hook)> {
+    let copy = |from: &str, to: Option<&str>, required: bool, exclude: &[&str]| Hook::Copy {
+        from: from.to_string(),
+        to: to.map(str::to_string),
+        required,
+        exclude: exclude.iter().map(|s| s.to_string()).collect(),
+    };
+    let run = |command: &str, timeout: Option<u64>| Hook::Run {
+        command: command.to_string(),
+        timeout,
+    };
+    vec![
+        (
+            "post_create",
+            "Copy a file; `required` fails the hook when the source is missing",
+            copy(".env", None, true, &[]),
+        ),
+        (
+            "post_create",
+            "Copy to a different path; a `to` ending in / copies into that directory",
+            copy(".env.local", Some("config/"), false, &[]),
+        ),
+        (
+            "post_create",
+            "Directories are copied recursively, skipping `exclude` glob patterns",
+            copy("node_modules", None, false, &["*.log", ".cache"]),
+        ),
+        (
+            "post_create",
+            "Run a command in the new worktree, killed after `timeout` seconds",
+            run("npm install", Some(600)),
+        ),
+        (
+            "pre_remove",
+            "Runs in the worktree before `gj exit`; a failure aborts the exit (unless --force)",
+            run("git diff --quiet @{upstream}", None),
+        ),
+        (
+            "after_exit",
+            "Runs in the directory `gj exit` returns to",
+            run("git fetch --prune", None),
+        ),
+        (
+            "post_last_exit",
+            "Runs in the origin repo once its last gj worktree is removed",
+            run("docker compose down", None),
+        ),
+    ]
+}
+
+/// Commented-out examples of every hook type, appended by `gj init --example`
+///
+/// Lines starting with "## " describe an example; lines starting with "# " are the
+/// example itself and can be uncommented as-is.
+fn example_section() -> Result<String> {
+    let mut out = String::from(
+        "\n## Examples of every hook type (written by `gj init --example`)\n\
+        ## Uncomment the lines starting with a single # to use them.\n\
+        #\n",
+    );
+    out.push_str(&format!("# [repos.{}]\n", EXAMPLE_REPO));
+    out.push_str(&format!("# path = \"~/dev/{}\"\n", EXAMPLE_REPO));
+    for (stage, description, hook) in example_hooks() {
+        out.push_str("#\n");
+        out.push_str(&format!("## {}\n", description));
+        out.push_str(&format!("# [[repos.{}.hooks.{}]]\n", EXAMPLE_REPO, stage));
+        for line in toml::to_string(&hook)?.lines() {
+            out.push_str(&format!("# {}\n", line));
+        }
+    }
+    Ok(out)
+}
+
 /// Execute the `gj init` command
-pub fn run(force: bool) -> Result<()> {
+pub fn run(force: bool, example: bool) -> Result<()> {
     let config_dir = Config::config_dir()?;
-    let config_path = Config::config_path()?;
+    let existing_path = Config::config_path()?;
 
-    // Check if config file already exists
-    if config_path.exists() && !force {
+    // Check if config file (TOML or JSON) already exists
+    if existing_path.exists() && !force {
         bail!(
             "Configuration file already exists at {}\n\n\
             Use `gj init --force` to overwrite.",
-            config_path.display()
+            existing_path.display()
         );
     }
 
+    // The template is TOML; it takes precedence over an existing config.json
+    let config_path = config_dir.join(CONFIG_FILE);
+
     // Create config directory if it doesn't exist
     if !config_dir.exists() {
         fs::create_dir_all(&config_dir)?;
     }
 
     // Write the configuration template
-    fs::write(&config_path, CONFIG_TEMPLATE)?;
+    let mut content = CONFIG_TEMPLATE.to_string();
+    if example {
+        content.push_str(&example_section()?);
+    }
+    fs::write(&config_path, content)?;
 
     eprintln!("Created configuration file at {}", config_path.display());
     eprintln!("\nEdit this file to configure your repositories and hooks.");
@@ -71,4 +221,46 @@ mod tests {
         let result: Result<Config, _> = toml::from_str(CONFIG_TEMPLATE);
         assert!(result.is_ok(), "Template should be valid TOML: {:?}", result.err());
     }
+
+    /// Index of each `Hook` variant; adding a variant fails to compile until it gets one
+    fn hook_kind(hook: &Hook) -> usize {
+        match hook {
+            Hook::Copy { .. } => 0,
+            Hook::Run { .. } => 1,
+        }
+    }
+    const HOOK_KINDS: usize = 2;
+
+    #[test]
+    fn test_example_covers_every_hook_type() {
+        let mut seen = [false; HOOK_KINDS];
+        for (_, _, hook) in example_hooks() {
+            seen[hook_kind(&hook)] = true;
+        }
+        assert!(seen.iter().all(|s| *s), "every Hook variant needs an example");
+    }
+
+    #[test]
+    fn test_example_section_uncomments_to_valid_config() {
+        let section = example_section().unwrap();
+        let content = CONFIG_TEMPLATE.to_string() + &section;
+        let config: Config = toml::from_str(&content).unwrap();
+        assert!(config.repos.is_empty());
+
+        let uncommented: String = section
+            .lines()
+            .map(|line| line.strip_prefix("# ").unwrap_or(line))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let config: Config = toml::from_str(&(CONFIG_TEMPLATE.to_string() + &uncommented)).unwrap();
+        let hooks = &config.repos[EXAMPLE_REPO].hooks;
+        assert_eq!(hooks.post_create.len(), 4);
+        assert_eq!(hooks.pre_remove.len(), 1);
+        assert_eq!(hooks.after_exit.len(), 1);
+        assert_eq!(hooks.post_last_exit.len(), 1);
+        assert!(matches!(
+            &hooks.post_create[1],
+            Hook::Copy { to: Some(to), .. } if to == "config/"
+        ));
+    }
 }
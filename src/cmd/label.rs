@@ -0,0 +1,97 @@
+use anyhow::{bail, Result};
+
+use crate::state;
+
+/// Execute `gj label`: add `labels` to a worktree, or remove them with `remove`
+pub fn run(name: &str, labels: &[String], remove: bool) -> Result<()> {
+    for label in labels {
+        validate_label(label)?;
+    }
+
+    let mut state = super::cd::find_worktree(name)?;
+    let updated = if remove {
+        remove_labels(&state.labels, labels)
+    } else {
+        add_labels(&state.labels, labels)
+    };
+    if updated != state.labels {
+        state.labels = updated;
+        state.save()?;
+    }
+
+    let path = state::display_path(&state.worktree_path);
+    if state.labels.is_empty() {
+        eprintln!("No labels: {}", path);
+    } else {
+        eprintln!("Labels of {}: {}", path, state.labels.join(", "));
+    }
+
+    Ok(())
+}
+
+/// A label must be non-empty without whitespace or commas, so list output stays unambiguous
+fn validate_label(label: &str) -> Result<()> {
+    if label.is_empty() || label.contains(|c: char| c.is_whitespace() || c == ',') {
+        bail!(
+            "Invalid label '{}': use a word without spaces or commas",
+            label
+        );
+    }
+    Ok(())
+}
+
+/// `current` followed by the new `labels` it doesn't have yet
+fn add_labels(current: &[String], labels: &[String]) -> Vec<String> {
+    let mut result = current.to_vec();
+    for label in labels {
+        if !result.contains(label) {
+            result.push(label.clone());
+        }
+    }
+    result
+}
+
+/// `current` without `labels`
+fn remove_labels(current: &[String], labels: &[String]) -> Vec<String> {
+    current
+        .iter()
+        .filter(|label| !labels.contains(label))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_add_labels() {
+        let current = labels(&["wip"]);
+        assert_eq!(
+            add_labels(&current, &labels(&["review", "wip", "review"])),
+            labels(&["wip", "review"])
+        );
+    }
+
+    #[test]
+    fn test_remove_labels() {
+        let current = labels(&["wip", "review"]);
+        assert_eq!(
+            remove_labels(&current, &labels(&["wip", "other"])),
+            labels(&["review"])
+        );
+    }
+
+    #[test]
+    fn test_validate_label() {
+        assert!(validate_label("experiment").is_ok());
+        assert!(validate_label("needs-review").is_ok());
+        assert!(validate_label("").is_err());
+        assert!(validate_label("two words").is_err());
+        assert!(validate_label("a,b").is_err());
+    }
+}
@@ -1,53 +1,397 @@
-use anyhow::Result;
-use chrono::Utc;
+use anyhow::{bail, Result};
+use chrono::{Duration, Utc};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
+use crate::config::{self, Config};
+use crate::git;
+use crate::glob::glob_match;
 use crate::state;
 
+/// Arguments for the `gj list` command
+#[derive(Debug, Clone, clap::Args)]
+pub struct ListArgs {
+    /// Only show worktrees whose branch matches a shell-style glob (e.g., 'pr-*')
+    #[arg(long)]
+    pub branch: Option<String>,
+    /// Only show worktrees with this label (see `gj label`)
+    #[arg(long)]
+    pub label: Option<String>,
+    /// Delete state for listed worktrees that no longer exist on disk
+    #[arg(long)]
+    pub prune: bool,
+    /// Also prune pinned worktrees
+    #[arg(long, requires = "prune")]
+    pub include_pinned: bool,
+    /// Prune worktrees of every repository, not just the current one
+    #[arg(long, requires = "prune")]
+    pub all_repos: bool,
+    /// Mark worktrees whose branch is merged into the default branch (slower)
+    #[arg(long)]
+    pub show_merged: bool,
+    /// Print `git worktree list --porcelain` style blocks for scripts
+    #[arg(long, conflicts_with = "show_merged")]
+    pub porcelain: bool,
+    /// Only show the N most recently created worktrees
+    #[arg(long, short = 'n', value_name = "N")]
+    pub limit: Option<usize>,
+    /// Flag worktrees older than this (e.g., 14d; default: `default.stale_after`)
+    #[arg(long, value_name = "DURATION", value_parser = config::parse_duration)]
+    pub age_threshold: Option<Duration>,
+    /// Also show this repository's git worktrees that gj doesn't manage
+    #[arg(long)]
+    pub include_unmanaged: bool,
+}
+
 /// Execute the `gj list` command
-pub fn run() -> Result<()> {
-    let states = state::list_all_states()?;
+pub fn run(args: ListArgs) -> Result<()> {
+    let ListArgs {
+        branch: branch_pattern,
+        label,
+        prune,
+        include_pinned,
+        all_repos,
+        show_merged,
+        porcelain,
+        limit,
+        age_threshold,
+        include_unmanaged,
+    } = args;
+
+    let config = Config::load().ok();
+    if let Some(config) = &config {
+        auto_prune(config);
+    }
+
+    // --age-threshold wins over default.stale_after
+    let stale_after = match (age_threshold, &config) {
+        (Some(threshold), _) => Some(threshold),
+        (None, Some(config)) => config.get_stale_after().unwrap_or_else(|e| {
+            eprintln!("Warning: {:#}", e);
+            None
+        }),
+        (None, None) => None,
+    };
+    let color = crate::error::use_color(&std::io::stdout());
+
+    // Pruning is limited to the current repository unless --all-repos is given
+    let prune_scope = if prune && !all_repos {
+        match current_origin_repo() {
+            Some(repo) => Some(repo),
+            None => bail!(
+                "Not in a git repository. Use `gj list --prune --all-repos` \
+                to prune worktrees of every repository."
+            ),
+        }
+    } else {
+        None
+    };
 
-    if states.is_empty() {
+    let mut states = state::list_all_states()?;
+    let mut unmanaged = if include_unmanaged {
+        unmanaged_worktrees(&states)
+    } else {
+        Vec::new()
+    };
+
+    if states.is_empty() && unmanaged.is_empty() {
         eprintln!("No managed worktrees found.");
         return Ok(());
     }
 
+    if let Some(pattern) = &branch_pattern {
+        states.retain(|s| glob_match(pattern, &s.branch));
+        unmanaged.retain(|w| w.branch.as_deref().is_some_and(|b| glob_match(pattern, b)));
+
+        if states.is_empty() && unmanaged.is_empty() {
+            eprintln!("No managed worktrees match branch '{}'.", pattern);
+            return Ok(());
+        }
+    }
+
+    // Worktrees gj doesn't manage have no labels
+    if let Some(label) = &label {
+        states.retain(|s| s.labels.contains(label));
+        unmanaged.clear();
+
+        if states.is_empty() {
+            eprintln!("No managed worktrees are labeled '{}'.", label);
+            return Ok(());
+        }
+    }
+
+    // States are sorted newest first, so this keeps the most recent ones
+    let hidden = match limit {
+        Some(limit) if states.len() > limit => {
+            let hidden = states.len() - limit;
+            states.truncate(limit);
+            hidden
+        }
+        _ => 0,
+    };
+
     let now = Utc::now();
+    let current_dir = std::env::current_dir().ok();
+    let mut missing = Vec::new();
+    // Merged branches per origin repository (only with --show-merged)
+    let mut merged_cache: HashMap<PathBuf, Vec<String>> = HashMap::new();
 
     for state in states {
+        if porcelain {
+            print!("{}", format_porcelain_entry(&state));
+            if !state.worktree_path.exists() {
+                missing.push(state);
+            }
+            continue;
+        }
+
         // Get the last two path segments for display name
         let display_name = get_display_name(&state.worktree_path);
 
-        // Calculate relative time
+        // Calculate relative time, flagging worktrees past the stale threshold
         let relative_time = format_relative_time(now, state.created_at);
+        let relative_time = match stale_after {
+            Some(threshold) if now.signed_duration_since(state.created_at) > threshold => {
+                format_stale(&relative_time, color)
+            }
+            _ => relative_time,
+        };
 
         // Check if worktree still exists
-        let exists_marker = if state.worktree_path.exists() {
-            ""
+        let exists = state.worktree_path.exists();
+        let exists_marker = if exists { "" } else { " (not found)" };
+        let pinned_marker = if state.pinned { " 📌" } else { "" };
+        let labels = format_labels(&state.labels);
+
+        let merged_marker = if show_merged
+            && merged_into_default(&mut merged_cache, &state.origin_repo, &state.branch)
+        {
+            " (merged)"
         } else {
-            " (not found)"
+            ""
+        };
+
+        // Mark the worktree the user is standing in
+        let current_marker = match &current_dir {
+            Some(dir) if is_within(dir, &state.worktree_path) => "*",
+            _ => " ",
         };
 
         println!(
-            "{:<30} {:<40} {}{}",
+            "{} {:<30} {:<40} {}{}{}{}{}",
+            current_marker,
             display_name,
             state.branch,
             relative_time,
-            exists_marker
+            pinned_marker,
+            labels,
+            exists_marker,
+            merged_marker
+        );
+
+        if !exists {
+            missing.push(state);
+        }
+    }
+
+    for worktree in unmanaged {
+        if porcelain {
+            print!("{}", format_unmanaged_porcelain_entry(&worktree));
+            continue;
+        }
+
+        let current_marker = match &current_dir {
+            Some(dir) if is_within(dir, &worktree.path) => "*",
+            _ => " ",
+        };
+        println!(
+            "{} {:<30} {:<40} (unmanaged)",
+            current_marker,
+            get_display_name(&worktree.path),
+            worktree.branch.as_deref().unwrap_or("(detached)")
         );
     }
 
+    if hidden > 0 {
+        eprintln!("… and {} more (omit --limit to show all)", hidden);
+    }
+
+    // Delete state for the listed worktrees that are gone, keeping pinned ones
+    if prune {
+        let (missing, other_repos): (Vec<_>, Vec<_>) = missing
+            .into_iter()
+            .partition(|s| in_scope(s, prune_scope.as_deref()));
+        if !other_repos.is_empty() {
+            eprintln!(
+                "Skipped {} missing worktree(s) of other repositories (use --all-repos to prune them)",
+                other_repos.len()
+            );
+        }
+
+        for state in missing {
+            if state.pinned && !include_pinned {
+                eprintln!(
+                    "Kept pinned worktree: {} (use --include-pinned to prune it)",
+                    state::display_path(&state.worktree_path)
+                );
+                continue;
+            }
+            state.delete()?;
+            eprintln!(
+                "Pruned stale worktree: {}",
+                state::display_path(&state.worktree_path)
+            );
+        }
+    }
+
     Ok(())
 }
 
-/// Get the display name from a worktree path (everything after "worktrees/")
-/// Example: ~/.gj/worktrees/mirakui/my_repo/gj/20260205_hello -> mirakui/my_repo/gj/20260205_hello
-fn get_display_name(path: &std::path::Path) -> String {
+/// Labels shown after a worktree's age, e.g. " [wip, review]"
+fn format_labels(labels: &[String]) -> String {
+    if labels.is_empty() {
+        String::new()
+    } else {
+        format!(" [{}]", labels.join(", "))
+    }
+}
+
+/// Format a state as a `git worktree list --porcelain` style block, ending with a blank line
+fn format_porcelain_entry(state: &state::WorktreeState) -> String {
+    let labels: String = state
+        .labels
+        .iter()
+        .map(|label| format!("gj-label {}\n", label))
+        .collect();
+    format!(
+        "worktree {}\nbranch refs/heads/{}\ngj-origin {}\ngj-created {}\n{}{}\n",
+        state.worktree_path.display(),
+        state.branch,
+        state.origin_repo.display(),
+        state
+            .created_at
+            .to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        if state.pinned { "gj-pinned\n" } else { "" },
+        labels
+    )
+}
+
+/// Linked worktrees of the current repository that have no gj state.
+/// Outside a repository there are none to show.
+fn unmanaged_worktrees(states: &[state::WorktreeState]) -> Vec<git::WorktreeEntry> {
+    let Ok(repo_root) = git::get_repo_root() else {
+        return Vec::new();
+    };
+
+    match git::list_worktrees(&repo_root) {
+        // The first entry is the main worktree, i.e. the origin repository itself
+        Ok(worktrees) => worktrees
+            .into_iter()
+            .skip(1)
+            .filter(|w| !states.iter().any(|s| same_path(&s.worktree_path, &w.path)))
+            .collect(),
+        Err(e) => {
+            eprintln!("Warning: Could not list git worktrees: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// The origin repository of the current directory: the main repository when inside a
+/// linked worktree. `None` outside a repository.
+pub fn current_origin_repo() -> Option<PathBuf> {
+    let repo_root = git::get_repo_root().ok()?;
+    Some(git::main_repo_root(&repo_root).unwrap_or(repo_root))
+}
+
+/// Prune stale state if `default.auto_prune` is enabled. Like `--prune` without
+/// `--all-repos`, only worktrees of the current repository are pruned, and nothing
+/// outside a repository.
+pub fn auto_prune(config: &Config) {
+    if !config.default.auto_prune {
+        return;
+    }
+    let Some(repo) = current_origin_repo() else {
+        return;
+    };
+
+    match state::prune_stale_states(|s| in_scope(s, Some(&repo))) {
+        Ok(pruned) => {
+            for state in pruned {
+                eprintln!(
+                    "Pruned stale worktree: {}",
+                    state::display_path(&state.worktree_path)
+                );
+            }
+        }
+        Err(e) => eprintln!("Warning: Failed to prune stale state: {}", e),
+    }
+}
+
+/// Whether a worktree belongs to the repository scope (`None` is every repository)
+pub fn in_scope(state: &state::WorktreeState, scope: Option<&Path>) -> bool {
+    scope.is_none_or(|repo| same_path(&state.origin_repo, repo))
+}
+
+/// Format an unmanaged worktree as a porcelain block, marked with `gj-unmanaged`
+fn format_unmanaged_porcelain_entry(worktree: &git::WorktreeEntry) -> String {
+    let head = match &worktree.branch {
+        Some(branch) => format!("branch refs/heads/{}", branch),
+        None => "detached".to_string(),
+    };
+    format!(
+        "worktree {}\n{}\ngj-unmanaged\n\n",
+        worktree.path.display(),
+        head
+    )
+}
+
+/// Whether a branch is merged into its origin repository's default branch
+pub fn merged_into_default(
+    cache: &mut HashMap<PathBuf, Vec<String>>,
+    origin_repo: &Path,
+    branch: &str,
+) -> bool {
+    let merged = cache.entry(origin_repo.to_path_buf()).or_insert_with(|| {
+        let result = git::get_default_branch(origin_repo)
+            .and_then(|default| git::merged_branches(&default, origin_repo));
+        result.unwrap_or_else(|e| {
+            eprintln!(
+                "Warning: Could not check merged branches in {}: {}",
+                state::display_path(origin_repo),
+                e
+            );
+            Vec::new()
+        })
+    });
+    merged.iter().any(|b| b == branch)
+}
+
+/// Whether two paths point at the same location (comparing canonical paths)
+fn same_path(a: &Path, b: &Path) -> bool {
+    let canonical = |p: &Path| p.canonicalize().unwrap_or_else(|_| p.to_path_buf());
+    canonical(a) == canonical(b)
+}
+
+/// Whether `dir` is `worktree_path` or inside it (comparing canonical paths)
+pub fn is_within(dir: &Path, worktree_path: &Path) -> bool {
+    let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    let worktree_path = worktree_path
+        .canonicalize()
+        .unwrap_or_else(|_| worktree_path.to_path_buf());
+    dir.starts_with(worktree_path)
+}
+
+/// Get the display name from a worktree path (everything after "worktrees/", without the github.com segment)
+/// Example: ~/.gj/worktrees/github.com/mirakui/my_repo/gj/20260205_hello -> mirakui/my_repo/gj/20260205_hello
+pub fn get_display_name(path: &std::path::Path) -> String {
     let path_str = path.to_string_lossy();
 
     // Find "worktrees/" in the path and return everything after it
     if let Some(idx) = path_str.find("worktrees/") {
-        return path_str[idx + "worktrees/".len()..].to_string();
+        let name = &path_str[idx + "worktrees/".len()..];
+        let github_prefix = format!("{}/", crate::git::GITHUB_HOST);
+        return name.strip_prefix(&github_prefix).unwrap_or(name).to_string();
     }
 
     // Fallback: return last 2 components if "worktrees/" not found
@@ -67,8 +411,17 @@ fn get_display_name(path: &std::path::Path) -> String {
         .join("/")
 }
 
+/// Highlight the relative time of a stale worktree (yellow when coloring) with a ⚠ marker
+fn format_stale(relative_time: &str, color: bool) -> String {
+    if color {
+        format!("\x1b[33m{} ⚠\x1b[0m", relative_time)
+    } else {
+        format!("{} ⚠", relative_time)
+    }
+}
+
 /// Format a relative time string
-fn format_relative_time(now: chrono::DateTime<Utc>, created: chrono::DateTime<Utc>) -> String {
+pub fn format_relative_time(now: chrono::DateTime<Utc>, created: chrono::DateTime<Utc>) -> String {
     let duration = now.signed_duration_since(created);
 
     if duration.num_days() > 0 {
@@ -102,6 +455,67 @@ mod tests {
     use super::*;
     use std::path::PathBuf;
 
+    #[test]
+    fn test_format_porcelain_entry() {
+        let mut state = state::WorktreeState::new(
+            PathBuf::from("/wt/github.com/mirakui/gj/pr-1"),
+            PathBuf::from("/dev/gj"),
+            "fix-bug".to_string(),
+        );
+        state.created_at = "2026-02-05T10:30:00Z".parse().unwrap();
+
+        assert_eq!(
+            format_porcelain_entry(&state),
+            "worktree /wt/github.com/mirakui/gj/pr-1\n\
+             branch refs/heads/fix-bug\n\
+             gj-origin /dev/gj\n\
+             gj-created 2026-02-05T10:30:00Z\n\n"
+        );
+
+        state.pinned = true;
+        assert!(format_porcelain_entry(&state)
+            .ends_with("gj-created 2026-02-05T10:30:00Z\ngj-pinned\n\n"));
+
+        state.labels = vec!["wip".to_string(), "review".to_string()];
+        assert!(format_porcelain_entry(&state)
+            .ends_with("gj-pinned\ngj-label wip\ngj-label review\n\n"));
+    }
+
+    #[test]
+    fn test_format_labels() {
+        assert_eq!(format_labels(&[]), "");
+        let labels = vec!["wip".to_string(), "review".to_string()];
+        assert_eq!(format_labels(&labels), " [wip, review]");
+    }
+
+    #[test]
+    fn test_in_scope() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let repo = temp_dir.path().join("repo");
+        std::fs::create_dir(&repo).unwrap();
+        let state = state::WorktreeState::new(
+            temp_dir.path().join("wt/gone"),
+            repo.join("."),
+            "gone".to_string(),
+        );
+
+        assert!(in_scope(&state, None));
+        assert!(in_scope(&state, Some(&repo)));
+        assert!(!in_scope(&state, Some(&temp_dir.path().join("other"))));
+    }
+
+    #[test]
+    fn test_is_within() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let worktree = temp_dir.path().join("gj/20260205_hello");
+        std::fs::create_dir_all(worktree.join("src")).unwrap();
+
+        assert!(is_within(&worktree, &worktree));
+        assert!(is_within(&worktree.join("src"), &worktree));
+        assert!(!is_within(temp_dir.path(), &worktree));
+        assert!(!is_within(&temp_dir.path().join("gj/20260205_hello2"), &worktree));
+    }
+
     #[test]
     fn test_get_display_name_with_worktrees() {
         let path = PathBuf::from("/Users/test/.gj/worktrees/mirakui/my_repo/gj/20260205_hello");
@@ -111,6 +525,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_get_display_name_with_host() {
+        let path = PathBuf::from("/Users/test/.gj/worktrees/github.com/mirakui/my_repo/pr-123");
+        assert_eq!(get_display_name(&path), "mirakui/my_repo/pr-123");
+
+        let path = PathBuf::from("/Users/test/.gj/worktrees/ghe.example.com/mirakui/my_repo/pr-1");
+        assert_eq!(get_display_name(&path), "ghe.example.com/mirakui/my_repo/pr-1");
+    }
+
     #[test]
     fn test_get_display_name_fallback() {
         // Without "worktrees/" in path, falls back to last 2 components
@@ -118,6 +541,36 @@ mod tests {
         assert_eq!(get_display_name(&path), "my-repo/feature-branch");
     }
 
+    #[test]
+    fn test_format_unmanaged_porcelain_entry() {
+        let worktree = git::WorktreeEntry {
+            path: PathBuf::from("/src/app-hotfix"),
+            branch: Some("hotfix".to_string()),
+        };
+        assert_eq!(
+            format_unmanaged_porcelain_entry(&worktree),
+            "worktree /src/app-hotfix\nbranch refs/heads/hotfix\ngj-unmanaged\n\n"
+        );
+
+        let detached = git::WorktreeEntry {
+            path: PathBuf::from("/src/app-bisect"),
+            branch: None,
+        };
+        assert_eq!(
+            format_unmanaged_porcelain_entry(&detached),
+            "worktree /src/app-bisect\ndetached\ngj-unmanaged\n\n"
+        );
+    }
+
+    #[test]
+    fn test_format_stale() {
+        assert_eq!(format_stale("15 days ago", false), "15 days ago ⚠");
+        assert_eq!(
+            format_stale("15 days ago", true),
+            "\x1b[33m15 days ago ⚠\x1b[0m"
+        );
+    }
+
     #[test]
     fn test_format_relative_time() {
         let now = Utc::now();
@@ -1,8 +1,401 @@
+pub mod archive;
+pub mod base_dir;
 pub mod cd;
 pub mod checkout;
+pub mod config;
+pub mod doctor;
 pub mod exit;
+pub mod hook_plan;
 pub mod init;
+pub mod label;
 pub mod list;
 pub mod new;
+pub mod pin;
 pub mod pr;
+pub mod relink;
 pub mod shell_init;
+pub mod stats;
+pub mod status;
+pub mod version;
+
+use anyhow::{bail, Context, Result};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::{Config, Hook, RepoConfig};
+use crate::git::{self, GitHubRepo};
+use crate::history::{self, EventKind};
+use crate::hooks;
+use crate::state::WorktreeState;
+
+/// Options shared by the worktree creation commands (`new`, `pr`, `checkout`)
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct CreateOptions {
+    /// Do not record the worktree in gj state (it won't appear in `gj list` / `gj cd`)
+    #[arg(long)]
+    pub no_state: bool,
+    /// Print `path=` and `branch=` lines instead of the bare path (the shell wrapper won't cd)
+    #[arg(long)]
+    pub porcelain: bool,
+    /// Show the branch, worktree path and hooks without creating anything
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Stay in the current directory; the shell wrapper won't cd (default: `default.no_cd`)
+    #[arg(long, overrides_with = "cd")]
+    pub no_cd: bool,
+    /// Change into the worktree even if `no_cd` is configured
+    #[arg(long, overrides_with = "no_cd")]
+    pub cd: bool,
+    /// Place the worktree under this directory instead of the configured `base_dir`
+    #[arg(long, value_name = "PATH", value_parser = parse_base_dir)]
+    pub base_dir: Option<PathBuf>,
+}
+
+/// Parse `--base-dir`: expand `~` and `$VAR`, and resolve a relative path against the
+/// directory gj was started in, before anything changes into the repository
+fn parse_base_dir(value: &str) -> Result<PathBuf> {
+    let expanded = crate::config::expand(value);
+    std::path::absolute(&expanded).with_context(|| format!("Invalid path: {}", expanded))
+}
+
+impl CreateOptions {
+    /// Whether to stay put: `--no-cd` / `--cd` win over the configured default
+    pub fn skips_cd(&self, config: &Config, repo_config: Option<&RepoConfig>) -> bool {
+        self.no_cd || (!self.cd && config.get_no_cd(repo_config))
+    }
+}
+
+/// The repository to create worktrees from. Inside a gj worktree this is its origin
+/// repository, so new worktrees don't chain off each other; later git commands run in
+/// the current directory, so it changes there too.
+pub fn resolve_origin_root(git_root: PathBuf) -> Result<PathBuf> {
+    let Some(state) = WorktreeState::load(&git_root)? else {
+        return Ok(git_root);
+    };
+    if !state.origin_repo.is_dir() {
+        return Ok(git_root);
+    }
+
+    env::set_current_dir(&state.origin_repo).with_context(|| {
+        format!(
+            "Failed to change directory to {}",
+            state.origin_repo.display()
+        )
+    })?;
+    eprintln!(
+        "Inside gj worktree; using origin repository: {}",
+        crate::state::display_path(&state.origin_repo)
+    );
+    Ok(state.origin_repo)
+}
+
+/// Save the state of a newly created worktree unless `--no-state` was given
+pub fn save_state(state: &WorktreeState, options: &CreateOptions) -> Result<()> {
+    if options.no_state {
+        eprintln!(
+            "Warning: Not recording state; this worktree won't appear in `gj list` or `gj cd`"
+        );
+        return Ok(());
+    }
+
+    state.save()?;
+    history::record(EventKind::Create, state);
+    Ok(())
+}
+
+/// Copy ignored files from the origin repository into a new worktree if
+/// `default.copy_gitignored` is enabled. A failure only warns.
+pub fn copy_gitignored(config: &Config, git_root: &Path, worktree_path: &Path) {
+    if !config.default.copy_gitignored {
+        return;
+    }
+    let exclude = &config.default.copy_gitignored_exclude;
+    if let Err(e) = hooks::copy_gitignored(git_root, worktree_path, exclude) {
+        eprintln!("Warning: Could not copy ignored files: {:#}", e);
+    }
+}
+
+/// Ensure the base directory exists (creating it if needed) and is writable
+pub fn ensure_writable_dir(dir: &Path) -> Result<()> {
+    let writable = fs::create_dir_all(dir).is_ok() && {
+        let probe = dir.join(format!(".gj-write-test-{}", std::process::id()));
+        let ok = fs::write(&probe, b"").is_ok();
+        let _ = fs::remove_file(&probe);
+        ok
+    };
+
+    if !writable {
+        bail!(
+            "Base directory {} is not writable. Check its permissions or change `base_dir` in the configuration.",
+            dir.display()
+        );
+    }
+
+    Ok(())
+}
+
+/// Longest worktree path gj creates. Windows' MAX_PATH is 260, and files inside the
+/// worktree need room too; paths near the limit otherwise fail with obscure git errors.
+const MAX_WORKTREE_PATH_LEN: usize = 240;
+
+/// Fail with a clear message if a new worktree's path is too long
+pub fn check_path_length(worktree_path: &Path) -> Result<()> {
+    // Count characters, as MAX_PATH does, rather than bytes of a multi-byte name
+    let len = worktree_path.to_string_lossy().chars().count();
+    if len > MAX_WORKTREE_PATH_LEN {
+        bail!(
+            "Worktree path is too long ({} characters, at most {}): {}\n\
+            Use a shorter name (or `gj new --dir`), a shorter `base_dir`, or `default.flat_layout = true`.",
+            len,
+            MAX_WORKTREE_PATH_LEN,
+            worktree_path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Directory holding a repository's worktrees: {base_dir}/{host}/{owner}/{repo},
+/// or {base_dir}/{owner}/{repo} with `default.flat_layout`.
+/// `--base-dir` replaces base_dir. Ensures base_dir is writable, except with `--dry-run`.
+pub fn repo_worktree_dir(
+    config: &Config,
+    repo_config: Option<&RepoConfig>,
+    github_repo: &GitHubRepo,
+    options: &CreateOptions,
+) -> Result<PathBuf> {
+    let base_dir = match &options.base_dir {
+        Some(base_dir) => base_dir.clone(),
+        None => config.get_base_dir(repo_config),
+    };
+    if !options.dry_run {
+        ensure_writable_dir(&base_dir)?;
+    }
+
+    let mut dir = base_dir;
+    if !config.default.flat_layout {
+        dir.push(&github_repo.host);
+    }
+    Ok(dir.join(&github_repo.owner).join(&github_repo.repo))
+}
+
+/// Fetch `origin/<branch>`, or use the local remote-tracking ref when offline.
+/// If the fetch fails but the ref already exists locally, warn and use it.
+pub fn fetch_or_use_local(branch: &str, offline: bool, git_root: &Path) -> Result<()> {
+    fetch_or_use_local_with(branch, offline, git_root, || git::fetch_branch(branch))
+}
+
+/// Like `fetch_or_use_local`, with `fetch` updating `origin/<branch>` in a custom way
+pub fn fetch_or_use_local_with(
+    branch: &str,
+    offline: bool,
+    git_root: &Path,
+    fetch: impl FnOnce() -> Result<()>,
+) -> Result<()> {
+    let remote_ref = format!("refs/remotes/origin/{}", branch);
+
+    if offline {
+        if !git::ref_exists(&remote_ref, git_root)? {
+            bail!(
+                "origin/{} is not available locally. Run without --offline to fetch it.",
+                branch
+            );
+        }
+        eprintln!("Offline: using existing origin/{}", branch);
+        return Ok(());
+    }
+
+    if let Err(e) = fetch() {
+        if !git::ref_exists(&remote_ref, git_root)? {
+            return Err(e);
+        }
+        eprintln!("Warning: {}", e);
+        eprintln!("Using existing origin/{} (may be out of date)", branch);
+    }
+
+    Ok(())
+}
+
+/// Print a newly created worktree to stdout: the bare path, or key=value lines with `--porcelain`.
+/// With `no_cd` under the shell wrapper, the path goes to stderr so the wrapper doesn't cd.
+pub fn print_created(
+    worktree_path: &Path,
+    branch: &str,
+    options: &CreateOptions,
+    no_cd: bool,
+) {
+    if options.porcelain {
+        print!(
+            "{}",
+            format_porcelain(&[
+                ("path", worktree_path.display().to_string()),
+                ("branch", branch.to_string()),
+            ])
+        );
+    } else if no_cd && env::var_os(hooks::AFTER_CD_FILE_ENV).is_some() {
+        eprintln!("{}", worktree_path.display());
+    } else {
+        println!("{}", worktree_path.display());
+    }
+}
+
+/// Print what a creation command would do with `--dry-run`
+pub fn print_dry_run(branch: &str, worktree_path: &Path, base: &str, hooks: &[&Hook]) {
+    println!("Dry run: nothing was created");
+    println!("Branch: {}", branch);
+    println!("Path:   {}", worktree_path.display());
+    println!("Base:   {}", base);
+    if hooks.is_empty() {
+        println!("Hooks:  (none)");
+    } else {
+        println!("Hooks:");
+        for hook in hooks {
+            println!("  - {}", hook);
+        }
+    }
+}
+
+/// Format `key=value` lines for `--porcelain` output
+pub fn format_porcelain(fields: &[(&str, String)]) -> String {
+    fields
+        .iter()
+        .map(|(key, value)| format!("{}={}\n", key, value))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_path_length() {
+        let repo_dir = Path::new("/home/me/.gj/worktrees/github.com/me/app");
+        assert!(check_path_length(&repo_dir.join("gj/20260205_short")).is_ok());
+
+        let long_name = format!("gj/20260205_{}", "very-long-ticket-title-".repeat(10));
+        let err = check_path_length(&repo_dir.join(long_name)).unwrap_err();
+        assert!(err.to_string().contains("Worktree path is too long"));
+        assert!(err.to_string().contains("--dir"));
+
+        // Under 240 characters, but over 240 bytes in UTF-8
+        let japanese_name = "機能".repeat(50);
+        assert!(check_path_length(&repo_dir.join(japanese_name)).is_ok());
+    }
+
+    #[test]
+    fn test_format_porcelain() {
+        let output = format_porcelain(&[
+            ("path", "/wt/feature".to_string()),
+            ("merge_commit", "abc123".to_string()),
+        ]);
+        assert_eq!(output, "path=/wt/feature\nmerge_commit=abc123\n");
+    }
+
+    #[test]
+    fn test_ensure_writable_dir() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("nested").join("worktrees");
+
+        ensure_writable_dir(&base_dir).unwrap();
+        assert!(base_dir.is_dir());
+        assert_eq!(fs::read_dir(&base_dir).unwrap().count(), 0);
+
+        // A file where the directory should be cannot be used
+        let file = temp_dir.path().join("file");
+        fs::write(&file, "").unwrap();
+        let err = ensure_writable_dir(&file.join("worktrees")).unwrap_err();
+        assert!(err.to_string().contains("is not writable"));
+    }
+
+    #[test]
+    fn test_repo_worktree_dir() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.default.base_dir = Some(temp_dir.path().display().to_string());
+        let github_repo = GitHubRepo {
+            host: "github.com".to_string(),
+            owner: "mirakui".to_string(),
+            repo: "gj".to_string(),
+        };
+        let options = CreateOptions::default();
+
+        assert_eq!(
+            repo_worktree_dir(&config, None, &github_repo, &options).unwrap(),
+            temp_dir.path().join("github.com").join("mirakui").join("gj")
+        );
+
+        config.default.flat_layout = true;
+        assert_eq!(
+            repo_worktree_dir(&config, None, &github_repo, &options).unwrap(),
+            temp_dir.path().join("mirakui").join("gj")
+        );
+    }
+
+    #[test]
+    fn test_repo_worktree_dir_base_dir_override() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.default.base_dir = Some(temp_dir.path().join("configured").display().to_string());
+        let github_repo = GitHubRepo {
+            host: "github.com".to_string(),
+            owner: "mirakui".to_string(),
+            repo: "gj".to_string(),
+        };
+        let options = CreateOptions {
+            base_dir: Some(temp_dir.path().join("fast")),
+            ..Default::default()
+        };
+
+        let dir = repo_worktree_dir(&config, None, &github_repo, &options).unwrap();
+        assert_eq!(dir, temp_dir.path().join("fast/github.com/mirakui/gj"));
+        assert!(temp_dir.path().join("fast").is_dir());
+        assert!(!temp_dir.path().join("configured").exists());
+    }
+
+    #[test]
+    fn test_parse_base_dir() {
+        let cwd = std::env::current_dir().unwrap();
+        assert_eq!(parse_base_dir("rel").unwrap(), cwd.join("rel"));
+        assert_eq!(parse_base_dir("/fast/wt").unwrap(), PathBuf::from("/fast/wt"));
+
+        std::env::set_var("GJ_TEST_FAST_DISK", "/mnt/fast");
+        assert_eq!(
+            parse_base_dir("$GJ_TEST_FAST_DISK/wt").unwrap(),
+            PathBuf::from("/mnt/fast/wt")
+        );
+        std::env::remove_var("GJ_TEST_FAST_DISK");
+    }
+
+    #[test]
+    fn test_repo_worktree_dir_dry_run_creates_nothing() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("worktrees");
+        let mut config = Config::default();
+        config.default.base_dir = Some(base_dir.display().to_string());
+        let github_repo = GitHubRepo {
+            host: "github.com".to_string(),
+            owner: "mirakui".to_string(),
+            repo: "gj".to_string(),
+        };
+        let options = CreateOptions {
+            dry_run: true,
+            ..Default::default()
+        };
+
+        repo_worktree_dir(&config, None, &github_repo, &options).unwrap();
+        assert!(!base_dir.exists());
+    }
+
+    #[test]
+    fn test_porcelain_flag_parses() {
+        #[derive(clap::Parser)]
+        struct Cli {
+            #[command(flatten)]
+            options: CreateOptions,
+        }
+
+        let cli = <Cli as clap::Parser>::parse_from(["gj", "--porcelain"]);
+        assert!(cli.options.porcelain);
+        assert!(!cli.options.no_state);
+    }
+}
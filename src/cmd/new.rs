@@ -1,22 +1,165 @@
 use anyhow::{bail, Context, Result};
 use chrono::Utc;
 use petname::{Generator, Petnames};
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
 
+use super::CreateOptions;
 use crate::config::Config;
 use crate::git;
 use crate::hooks;
 use crate::state::WorktreeState;
 
+/// Arguments for the `gj new` command
+#[derive(Debug, Clone, clap::Args)]
+pub struct NewArgs {
+    /// Branch suffix (prompted interactively if not provided)
+    #[arg(conflicts_with = "random_suffix")]
+    pub branch_suffix: Option<String>,
+    /// Generate a random branch suffix automatically
+    #[arg(long)]
+    pub random_suffix: bool,
+    /// Carry uncommitted changes from the origin repository into the new worktree
+    #[arg(long)]
+    pub carry: bool,
+    /// Worktree directory name (defaults to the generated branch name)
+    #[arg(long)]
+    pub dir: Option<String>,
+    /// Ref to branch from (defaults to the repository's `default_base`, then current HEAD)
+    #[arg(long, value_name = "REF")]
+    pub from: Option<String>,
+    /// Branch prefix for this worktree (overrides the configured prefix)
+    #[arg(long)]
+    pub prefix: Option<String>,
+    /// Start an orphan branch with no history and no files
+    #[arg(long, conflicts_with_all = ["from", "carry"])]
+    pub empty: bool,
+    /// Switch into the worktree if it already exists instead of failing
+    #[arg(long)]
+    pub reuse: bool,
+    /// Use the branch name exactly as given, without prefix or date
+    #[arg(long, requires = "branch_suffix", conflicts_with = "prefix")]
+    pub raw: bool,
+    /// Push the new branch to origin and set it as upstream
+    #[arg(long, conflicts_with = "empty")]
+    pub push: bool,
+    /// Create a worktree for each name in FILE (one per line), staying in the current directory
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = [
+            "branch_suffix", "random_suffix", "dir", "carry", "reuse", "porcelain"
+        ]
+    )]
+    pub from_file: Option<PathBuf>,
+    #[command(flatten)]
+    pub options: CreateOptions,
+}
+
+/// What `gj new` did for a name
+#[derive(Debug, PartialEq)]
+enum Outcome {
+    /// The worktree was created (or would be, with --dry-run)
+    Created,
+    /// The worktree already existed and was reused
+    Existing,
+}
+
 /// Execute the `gj new` command
-pub fn run(branch_suffix: Option<String>, random_suffix: bool) -> Result<()> {
-    // Get the git repository root
-    let git_root = git::get_repo_root().context("Must be run inside a git repository")?;
+pub fn run(args: NewArgs) -> Result<()> {
+    match args.from_file.clone() {
+        Some(file) => run_batch(&file, args),
+        None => create(args, true).map(|_| ()),
+    }
+}
+
+/// Create a worktree for every name listed in `file`, continuing past failures.
+/// Existing worktrees are skipped, and no path is printed for the shell wrapper.
+fn run_batch(file: &Path, args: NewArgs) -> Result<()> {
+    let content = std::fs::read_to_string(file)
+        .with_context(|| format!("Failed to read {}", file.display()))?;
+    let names = parse_name_list(&content);
+    if names.is_empty() {
+        bail!("No names found in {}", file.display());
+    }
+
+    let (mut created, mut skipped) = (0, 0);
+    let mut failed = Vec::new();
+    for (i, name) in names.iter().enumerate() {
+        eprintln!("[{}/{}] {}", i + 1, names.len(), name);
+        let args = NewArgs {
+            branch_suffix: Some(name.to_string()),
+            reuse: true,
+            from_file: None,
+            ..args.clone()
+        };
+        match create(args, false) {
+            Ok(Outcome::Created) => created += 1,
+            Ok(Outcome::Existing) => {
+                eprintln!("Skipped: worktree already exists");
+                skipped += 1;
+            }
+            Err(e) => {
+                eprintln!("Failed: {:#}", e);
+                failed.push(*name);
+            }
+        }
+    }
+
+    eprintln!(
+        "Created {} worktree(s); skipped {}, failed {}",
+        created,
+        skipped,
+        failed.len()
+    );
+    if !failed.is_empty() {
+        bail!("Failed to create worktrees for: {}", failed.join(", "));
+    }
+    Ok(())
+}
+
+/// Names from a `--from-file` list: one per line, ignoring blank lines and `#` comments
+fn parse_name_list(content: &str) -> Vec<&str> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect()
+}
+
+/// Create one worktree. With `print_path`, its path is printed for the shell wrapper.
+fn create(args: NewArgs, print_path: bool) -> Result<Outcome> {
+    let NewArgs {
+        branch_suffix,
+        random_suffix,
+        carry,
+        dir,
+        from,
+        prefix,
+        empty,
+        reuse,
+        raw,
+        push,
+        from_file: _,
+        options,
+    } = args;
+
+    // Get the git repository root, offering registered repositories when outside one
+    let current_root = match git::get_repo_root() {
+        Ok(git_root) => git_root,
+        Err(e) => match select_registered_repo()? {
+            Some(git_root) => git_root,
+            None => return Err(e).context("Must be run inside a git repository"),
+        },
+    };
+    let git_root = super::resolve_origin_root(current_root.clone())?;
 
     // Load configuration (requires config file to exist)
     let config = Config::load_required()?;
 
-    // Find the repository configuration (optional - works without registration)
-    let repo_config = config.find_repo(&git_root).map(|(_, cfg)| cfg);
+    // Find the repository configuration, merged with .gj.toml (optional - works without registration)
+    let repo_config = config.resolve_repo(&git_root)?;
+    let repo_config = repo_config.as_ref();
 
     // Get GitHub repository info from remote URL
     let github_repo = git::get_github_repo_info()?;
@@ -25,43 +168,161 @@ pub fn run(branch_suffix: Option<String>, random_suffix: bool) -> Result<()> {
     let input_name = if random_suffix {
         generate_random_name()
     } else {
-        match branch_suffix {
+        let name = match branch_suffix {
             Some(name) => name,
+            None if !std::io::stdin().is_terminal() => {
+                let name = unattended_name(std::env::var(DEFAULT_NAME_ENV).ok());
+                eprintln!("No name given and stdin is not a terminal; using: {}", name);
+                name
+            }
             None => prompt_branch_name()?,
+        };
+        if config.default.slugify && !raw {
+            slugify(&name)
+        } else {
+            name
         }
     };
 
-    // Generate branch name: {prefix}/{YYYYMMDD}_{input}
-    let prefix = config.get_prefix(repo_config);
-    let date = Utc::now().format("%Y%m%d");
-    let branch = format!("{}/{}_{}", prefix, date, input_name);
+    // Generate branch name: {prefix}/{YYYYMMDD}_{input}, or the input verbatim with --raw
+    let branch = if raw {
+        input_name
+    } else {
+        let prefix = match prefix {
+            Some(prefix) => sanitize_prefix(&prefix)?,
+            None => config.get_prefix(repo_config).to_string(),
+        };
+        let date = Utc::now().format("%Y%m%d");
+        format!("{}/{}_{}", prefix, date, input_name)
+    };
+    git::check_branch_name(&branch)?;
 
-    // Generate worktree path: {base_dir}/{owner}/{repo}/{branch}
-    let base_dir = config.get_base_dir(repo_config);
-    let worktree_path = base_dir
-        .join(&github_repo.owner)
-        .join(&github_repo.repo)
-        .join(&branch);
+    // Generate worktree path: {base_dir}/{host}/{owner}/{repo}/{dir or branch}
+    let worktree_name = worktree_dir_name(dir.as_deref(), &branch)?;
+    let repo_dir = super::repo_worktree_dir(&config, repo_config, &github_repo, &options)?;
+    let worktree_path = repo_dir.join(&worktree_name);
+    super::check_path_length(&worktree_path)?;
 
     // Check if worktree path already exists
     if worktree_path.exists() {
+        if reuse {
+            if let Some(state) = WorktreeState::load(&worktree_path)? {
+                eprintln!(
+                    "Worktree already exists: {}",
+                    crate::state::display_path(&worktree_path)
+                );
+                eprintln!("Branch: {}", state.branch);
+                if print_path {
+                    let no_cd = options.skips_cd(&config, repo_config);
+                    super::print_created(&worktree_path, &state.branch, &options, no_cd);
+                }
+                return Ok(Outcome::Existing);
+            }
+        }
         bail!(
             "Worktree already exists at {}. Use `gj cd {}` to switch to it.",
             worktree_path.display(),
-            branch
+            worktree_name
         );
     }
 
+    check_branch_available(&branch, &git_root)?;
+
+    // Resolve the base ref: --from, then the repository's default_base, then current HEAD
+    let base = if empty {
+        None
+    } else {
+        from.as_deref()
+            .or_else(|| config.get_default_base(repo_config))
+    };
+    if let Some(base) = base {
+        if !git::ref_exists(base, &git_root)? {
+            bail!("Base ref '{}' does not exist", base);
+        }
+    }
+
+    if options.dry_run {
+        let base = match base {
+            _ if empty => "(orphan, no history)",
+            Some(base) => base,
+            None => "HEAD",
+        };
+        let hooks = config.get_hooks(repo_config);
+        super::print_dry_run(&branch, &worktree_path, base, &hooks);
+        return Ok(Outcome::Created);
+    }
+
+    // Stash uncommitted changes where the user is, so they can be carried over
+    let carried = if carry && git::has_uncommitted_changes(&current_root)? {
+        git::stash_push(&format!("gj carry: {}", branch), &current_root)?;
+        eprintln!("Stashed uncommitted changes");
+        true
+    } else {
+        false
+    };
+
     // Create the worktree
-    git::worktree_add_new_branch(&worktree_path, &branch)?;
+    let created = match base {
+        _ if empty => git::worktree_add_orphan(&worktree_path, &branch),
+        Some(base) => git::worktree_add_with_branch(&worktree_path, &branch, base),
+        None => git::worktree_add_new_branch(&worktree_path, &branch),
+    };
+    if let Err(e) = created {
+        if carried {
+            // Restore the changes to where they were stashed
+            if let Err(pop_err) = git::stash_pop(&current_root) {
+                eprintln!("Warning: Could not restore stashed changes: {}", pop_err);
+            }
+        }
+        return Err(e);
+    }
+
+    // Merge the repository's seed branch before carried changes touch the worktree
+    if let Some(seed) = config.get_seed_branch(repo_config).filter(|_| !empty) {
+        merge_seed_branch(seed, &branch, &worktree_path);
+    }
+
+    // Apply carried changes into the new worktree
+    if carried {
+        match git::stash_apply(&worktree_path) {
+            Ok(()) => {
+                eprintln!("Carried uncommitted changes into worktree");
+                // The changes are in place already, so a leftover stash is only clutter
+                if let Err(e) = git::stash_drop(&worktree_path) {
+                    eprintln!("Warning: Could not drop the carried stash: {}", e);
+                    eprintln!("Run `git stash drop` once you have checked the worktree.");
+                }
+            }
+            Err(e) => {
+                eprintln!("Warning: Could not apply carried changes: {}", e);
+                eprintln!(
+                    "The stash has been kept. Resolve conflicts or run `git stash pop` manually."
+                );
+            }
+        }
+    }
+
+    // Publish the branch; a rejected push (e.g. a protected name) keeps the worktree
+    if push {
+        match git::push_set_upstream(&branch, &worktree_path) {
+            Ok(()) => eprintln!("Pushed branch to origin/{}", branch),
+            Err(e) => eprintln!("Warning: Could not push branch: {:#}", e),
+        }
+    }
 
     // Save state
     let state = WorktreeState::new(worktree_path.clone(), git_root.clone(), branch.clone());
-    state.save()?;
+    super::save_state(&state, &options)?;
+
+    // Bring over local-only files such as .env before hooks that may rely on them
+    super::copy_gitignored(&config, &git_root, &worktree_path);
 
     // Execute hooks
     let all_hooks = config.get_hooks(repo_config);
-    if let Err(e) = hooks::execute_hooks(&all_hooks, &git_root, &worktree_path) {
+    let hook_env = config.get_hook_env(repo_config);
+    let parallel = config.default.parallel_hooks;
+    let result = hooks::execute_hooks(&all_hooks, &git_root, &worktree_path, &hook_env, parallel);
+    if let Err(e) = result {
         eprintln!("Warning: Hook failed: {}", e);
     }
 
@@ -71,11 +332,131 @@ pub fn run(branch_suffix: Option<String>, random_suffix: bool) -> Result<()> {
         crate::state::display_path(&worktree_path)
     );
     eprintln!("Branch: {}", branch);
-    println!("{}", worktree_path.display());
+    if empty {
+        eprintln!("Empty orphan branch (no history)");
+    } else if let Some(base) = base {
+        eprintln!("Based on: {}", base);
+    }
+    if print_path {
+        let no_cd = options.skips_cd(&config, repo_config);
+        super::print_created(&worktree_path, &branch, &options, no_cd);
+    }
+
+    Ok(Outcome::Created)
+}
 
+/// Merge `seed_branch` into the new branch. Failures only warn: a conflicting merge is
+/// aborted, leaving the branch as created.
+fn merge_seed_branch(seed: &str, branch: &str, worktree_path: &Path) {
+    match git::ref_exists(seed, worktree_path) {
+        Ok(true) => {}
+        Ok(false) => {
+            eprintln!("Warning: Seed branch '{}' does not exist; not merged", seed);
+            return;
+        }
+        Err(e) => {
+            eprintln!("Warning: Could not resolve seed branch '{}': {}", seed, e);
+            return;
+        }
+    }
+
+    let message = format!("Merge seed branch '{}' into {}", seed, branch);
+    match git::merge_branch_with_message(seed, &message, worktree_path) {
+        Ok(()) => eprintln!("Merged seed branch: {}", seed),
+        Err(e) => {
+            eprintln!("Warning: Could not merge seed branch '{}': {}", seed, e);
+            if let Ok(Some(_)) = git::in_progress_operation(worktree_path) {
+                if let Err(abort_err) = git::merge_abort(worktree_path) {
+                    eprintln!("Warning: Could not abort the merge: {}", abort_err);
+                    return;
+                }
+            }
+            eprintln!("The merge was aborted; merge '{}' manually if needed.", seed);
+        }
+    }
+}
+
+/// Let the user pick a registered repository and switch into it.
+/// Returns `None` if no repositories are registered or stdin is not interactive.
+fn select_registered_repo() -> Result<Option<PathBuf>> {
+    let Ok(config) = Config::load() else {
+        return Ok(None);
+    };
+    if config.repos.is_empty() || !std::io::stdin().is_terminal() {
+        return Ok(None);
+    }
+
+    let mut names: Vec<String> = config.repos.keys().cloned().collect();
+    names.sort();
+
+    let name = inquire::Select::new("Not in a git repository. Select repository:", names)
+        .prompt()
+        .context("Failed to get repository selection")?;
+
+    // Later git commands run in the current directory
+    let repo_path = config.registered_repo_path(&name)?;
+    std::env::set_current_dir(&repo_path)
+        .with_context(|| format!("Failed to change directory to {}", repo_path.display()))?;
+    eprintln!("Using repository: {}", name);
+
+    git::get_repo_root().map(Some)
+}
+
+/// Fail with a clear message if the branch already exists locally
+fn check_branch_available(branch: &str, git_root: &Path) -> Result<()> {
+    if git::local_branch_exists(branch, git_root)? {
+        bail!(
+            "Branch '{}' already exists. Use `gj cd {}` to switch to its worktree, or choose another name.",
+            branch,
+            branch
+        );
+    }
     Ok(())
 }
 
+/// Worktree directory name: the sanitized `--dir` value, or the branch name
+fn worktree_dir_name(dir: Option<&str>, branch: &str) -> Result<String> {
+    match dir {
+        Some(dir) => {
+            let dir = sanitize_name(dir.trim());
+            if dir.is_empty() {
+                bail!("Directory name cannot be empty");
+            }
+            Ok(dir)
+        }
+        None => Ok(branch.to_string()),
+    }
+}
+
+/// Sanitize a `--prefix` value, keeping `/` between non-empty segments
+/// Example: "team/hot fix/" -> "team/hot-fix"
+fn sanitize_prefix(prefix: &str) -> Result<String> {
+    let prefix = prefix
+        .split('/')
+        .map(|segment| sanitize_name(segment.trim()))
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>()
+        .join("/");
+
+    if prefix.is_empty() {
+        bail!("Branch prefix cannot be empty");
+    }
+
+    Ok(prefix)
+}
+
+/// Environment variable with the name `gj new` uses when none is given and stdin is not a terminal
+pub const DEFAULT_NAME_ENV: &str = "GJ_DEFAULT_NAME";
+
+/// Name used instead of prompting when nobody can answer (e.g. in CI):
+/// `$GJ_DEFAULT_NAME` if set, otherwise a random name
+fn unattended_name(default_name: Option<String>) -> String {
+    match default_name.as_deref().map(str::trim) {
+        Some(name) if !name.is_empty() => sanitize_name(name),
+        _ => generate_random_name(),
+    }
+}
+
 /// Prompt the user for a branch name
 fn prompt_branch_name() -> Result<String> {
     let random_name = generate_random_name();
@@ -117,10 +498,79 @@ fn sanitize_name(name: &str) -> String {
         .collect()
 }
 
+/// Turn a pasted title into a kebab-case slug, keeping a leading ticket ID as written
+/// Example: "PROJ-123: Fix the login page's redirect" -> "PROJ-123-fix-the-login-pages-redirect"
+fn slugify(title: &str) -> String {
+    let title = title.trim();
+    let first = title.split_whitespace().next().unwrap_or("");
+    let ticket = first.trim_end_matches(|c: char| c.is_ascii_punctuation());
+    let (ticket, rest) = if is_ticket_id(ticket) {
+        (Some(ticket), &title[first.len()..])
+    } else {
+        (None, title)
+    };
+
+    let mut slug = String::new();
+    for c in rest.chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if (c.is_whitespace() || c == '-' || c == '_' || c == '/') && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_matches('-');
+
+    match ticket {
+        Some(ticket) if slug.is_empty() => ticket.to_string(),
+        Some(ticket) => format!("{}-{}", ticket, slug),
+        None => slug.to_string(),
+    }
+}
+
+/// Whether `word` looks like an issue tracker key such as `PROJ-123`
+fn is_ticket_id(word: &str) -> bool {
+    match word.split_once('-') {
+        Some((key, number)) => {
+            key.starts_with(|c: char| c.is_ascii_uppercase())
+                && key.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+                && !number.is_empty()
+                && number.chars().all(|c| c.is_ascii_digit())
+        }
+        None => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_name_list() {
+        let content = "PROJ-1\n\n  # comment\n  PROJ-2  \nPROJ-3";
+        assert_eq!(parse_name_list(content), vec!["PROJ-1", "PROJ-2", "PROJ-3"]);
+        assert!(parse_name_list("# only a comment\n\n").is_empty());
+    }
+
+    #[test]
+    fn test_unattended_name() {
+        assert_eq!(unattended_name(Some("ci build".to_string())), "ci-build");
+        assert!(!unattended_name(Some("  ".to_string())).is_empty());
+        assert!(!unattended_name(None).is_empty());
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("PROJ-123 Some title"), "PROJ-123-some-title");
+        assert_eq!(
+            slugify("PROJ-123: Fix the login page's redirect!"),
+            "PROJ-123-fix-the-login-pages-redirect"
+        );
+        assert_eq!(slugify("  Add   dark mode (beta) "), "add-dark-mode-beta");
+        assert_eq!(slugify("snake_case / path"), "snake-case-path");
+        assert_eq!(slugify("ABC-9"), "ABC-9");
+        assert_eq!(slugify("Proj-123 title"), "proj-123-title");
+    }
+
     #[test]
     fn test_sanitize_name() {
         assert_eq!(sanitize_name("feature"), "feature");
@@ -131,6 +581,55 @@ mod tests {
         assert_eq!(sanitize_name("feature123"), "feature123");
     }
 
+    #[test]
+    fn test_worktree_dir_name() {
+        assert_eq!(
+            worktree_dir_name(None, "gj/20260205_hello").unwrap(),
+            "gj/20260205_hello"
+        );
+        assert_eq!(
+            worktree_dir_name(Some("short"), "gj/20260205_hello").unwrap(),
+            "short"
+        );
+        assert_eq!(
+            worktree_dir_name(Some(" my dir/x "), "gj/20260205_hello").unwrap(),
+            "my-dir-x"
+        );
+        assert!(worktree_dir_name(Some("  "), "gj/20260205_hello").is_err());
+    }
+
+    #[test]
+    fn test_sanitize_prefix() {
+        assert_eq!(sanitize_prefix("hotfix").unwrap(), "hotfix");
+        assert_eq!(sanitize_prefix("team/hot fix/").unwrap(), "team/hot-fix");
+        assert_eq!(sanitize_prefix("/a//b").unwrap(), "a/b");
+        assert!(sanitize_prefix(" / ").is_err());
+    }
+
+    #[test]
+    fn test_check_branch_available() {
+        let repo = tempfile::TempDir::new().unwrap();
+        let git = |args: &[&str]| {
+            let output = std::process::Command::new("git")
+                .args(["-c", "user.name=Test", "-c", "user.email=test@test.com"])
+                .args(args)
+                .current_dir(repo.path())
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {:?} failed", args);
+        };
+        git(&["init", "--quiet"]);
+        git(&["commit", "--quiet", "--allow-empty", "-m", "initial"]);
+
+        let branch = "gj/20260205_hello";
+        check_branch_available(branch, repo.path()).unwrap();
+
+        git(&["branch", branch]);
+        let err = check_branch_available(branch, repo.path()).unwrap_err();
+        assert!(err.to_string().contains("already exists"), "{}", err);
+        assert!(err.to_string().contains("gj cd gj/20260205_hello"));
+    }
+
     #[test]
     fn test_generate_random_name() {
         let name = generate_random_name();
@@ -0,0 +1,30 @@
+use anyhow::Result;
+
+use crate::error::GjError;
+use crate::state::{self, WorktreeState};
+
+/// Execute `gj pin` (`pinned = true`) or `gj unpin` (`pinned = false`)
+pub fn run(name: Option<String>, pinned: bool) -> Result<()> {
+    let mut state = match name {
+        Some(name) => super::cd::find_worktree(&name)?,
+        None => WorktreeState::load_current()?.ok_or(GjError::NotInWorktree)?,
+    };
+
+    let path = state::display_path(&state.worktree_path);
+    match (state.pinned, pinned) {
+        (true, true) => eprintln!("Already pinned: {}", path),
+        (false, false) => eprintln!("Not pinned: {}", path),
+        (_, true) => {
+            state.pinned = true;
+            state.save()?;
+            eprintln!("Pinned worktree: {}", path);
+        }
+        (_, false) => {
+            state.pinned = false;
+            state.save()?;
+            eprintln!("Unpinned worktree: {}", path);
+        }
+    }
+
+    Ok(())
+}
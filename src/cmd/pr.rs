@@ -1,73 +1,351 @@
 use anyhow::{bail, Context, Result};
+use std::path::Path;
 
+use super::CreateOptions;
 use crate::config::Config;
+use crate::error::GjError;
 use crate::git;
 use crate::hooks;
 use crate::state::WorktreeState;
 
+/// Arguments for the `gj pr` command
+#[derive(Debug, Clone, clap::Args)]
+pub struct PrArgs {
+    /// PR number (defaults to the current branch's PR, else selected interactively)
+    #[arg(conflicts_with = "list")]
+    pub number: Option<u32>,
+    /// Select from open PRs interactively, even if the current branch has a PR
+    #[arg(long)]
+    pub list: bool,
+    /// Don't fetch; use the existing `origin/<branch>` ref
+    #[arg(long)]
+    pub offline: bool,
+    /// Comment on the PR after creating the worktree (default: `default.pr_comment`)
+    #[arg(long, value_name = "TEXT", num_args = 0..=1, default_missing_value = "")]
+    pub comment: Option<String>,
+    /// Remove an existing worktree for the PR and recreate it at the latest head
+    #[arg(long)]
+    pub force_recreate: bool,
+    /// With --force-recreate, discard uncommitted changes in the existing worktree
+    #[arg(long, requires = "force_recreate")]
+    pub force: bool,
+    #[command(flatten)]
+    pub options: CreateOptions,
+}
+
 /// Execute the `gj pr` command
-pub fn run(pr_number: u32) -> Result<()> {
+pub fn run(args: PrArgs) -> Result<()> {
+    let PrArgs {
+        number: pr_number,
+        list,
+        offline,
+        comment,
+        force_recreate,
+        force,
+        options,
+    } = args;
+
+    // Without a number, use the current branch's PR, then fall back to the picker
+    let pr_number = match pr_number {
+        Some(number) => number,
+        None if list => select_pr()?,
+        None => match git::current_branch_pr() {
+            Some(number) => {
+                eprintln!("Using PR #{} for the current branch", number);
+                number
+            }
+            None => select_pr()?,
+        },
+    };
+
     // Get the git repository root
     let git_root = git::get_repo_root().context("Must be run inside a git repository")?;
+    let git_root = super::resolve_origin_root(git_root)?;
 
     // Load configuration (requires config file to exist)
     let config = Config::load_required()?;
 
-    // Find the repository configuration (optional - works without registration)
-    let repo_config = config.find_repo(&git_root).map(|(_, cfg)| cfg);
+    // Find the repository configuration, merged with .gj.toml (optional - works without registration)
+    let repo_config = config.resolve_repo(&git_root)?;
+    let repo_config = repo_config.as_ref();
 
     // Get GitHub repository info from remote URL
     let github_repo = git::get_github_repo_info()?;
 
-    // Get PR branch name using gh CLI
-    let pr_branch = git::get_pr_branch(pr_number)?;
+    // Get PR head branch using gh CLI
+    let head = git::get_pr_head(pr_number)?;
+    let pr_branch = head.local_branch();
 
-    // Generate worktree path: {base_dir}/{owner}/{repo}/pr-{number}
-    let base_dir = config.get_base_dir(repo_config);
+    // Generate worktree path: {base_dir}/{host}/{owner}/{repo}/pr-{number}
     let worktree_name = pr_worktree_name(pr_number);
-    let worktree_path = base_dir
-        .join(&github_repo.owner)
-        .join(&github_repo.repo)
-        .join(&worktree_name);
+    let repo_dir = super::repo_worktree_dir(&config, repo_config, &github_repo, &options)?;
+    let worktree_path = repo_dir.join(&worktree_name);
+    super::check_path_length(&worktree_path)?;
 
-    // Check if worktree path already exists
-    if worktree_path.exists() {
-        bail!(
-            "Worktree already exists at {}. Use `gj cd {}` to switch to it.",
-            worktree_path.display(),
-            worktree_name
-        );
+    // Check if worktree path already exists; --force-recreate replaces it after fetching
+    let stale = if worktree_path.exists() {
+        if !force_recreate {
+            bail!(
+                "Worktree already exists at {}. Use `gj cd {}` to switch to it, \
+                or --force-recreate to rebuild it at the PR's latest head.",
+                worktree_path.display(),
+                worktree_name
+            );
+        }
+        Some(stale_worktree(&worktree_path, force)?)
+    } else {
+        None
+    };
+
+    // A fork's head branch is not on origin, so use the PR's head ref instead
+    let remote_branch = if head.is_cross_repository {
+        format!("pull/{}/head", pr_number)
+    } else {
+        head.branch.clone()
+    };
+    let git_ref = format!("origin/{}", remote_branch);
+
+    if options.dry_run {
+        let hooks = config.get_hooks(repo_config);
+        super::print_dry_run(&pr_branch, &worktree_path, &git_ref, &hooks);
+        if stale.is_some() {
+            println!("The existing worktree would be removed and recreated");
+        }
+        return Ok(());
     }
 
+    // Where origin had the PR before fetching, to tell a force-push from unpushed work
+    let previous_head = stale
+        .as_ref()
+        .and_then(|_| git::rev_parse(&git_ref, &git_root).ok());
+
     // Fetch the PR branch
-    eprintln!("Fetching PR #{}...", pr_number);
-    git::fetch_branch(&pr_branch)?;
+    if !offline {
+        eprintln!("Fetching PR #{}...", pr_number);
+    }
+    if head.is_cross_repository {
+        super::fetch_or_use_local_with(&remote_branch, offline, &git_root, || {
+            git::fetch_pull_head(pr_number)
+        })?;
+    } else {
+        super::fetch_or_use_local(&remote_branch, offline, &git_root)?;
+    }
 
-    // Create the worktree with the PR branch name, tracking origin
-    let git_ref = format!("origin/{}", pr_branch);
-    git::worktree_add_with_branch(&worktree_path, &pr_branch, &git_ref)?;
+    // The old checkout goes only once the new head has been fetched
+    if let Some(stale) = stale {
+        if !force {
+            check_no_local_commits(&stale, &git_ref, previous_head, &git_root)?;
+        }
+        super::exit::remove_worktree(&stale, force, true)?;
+        eprintln!(
+            "Removed stale worktree: {}",
+            crate::state::display_path(&stale.worktree_path)
+        );
+    }
+
+    // Create the worktree with the PR branch name, or on that branch if it exists locally
+    // (e.g. the PR is yours and you pushed it from here)
+    if git::local_branch_exists(&pr_branch, &git_root)? {
+        add_on_local_branch(&worktree_path, &pr_branch, &git_ref, &git_root)?;
+    } else {
+        git::worktree_add_with_branch(&worktree_path, &pr_branch, &git_ref)?;
+    }
 
-    // Set upstream tracking
-    git::set_upstream(&worktree_path, &pr_branch, &git_ref)?;
+    // Set upstream tracking to the head branch (forks keep tracking the PR's head ref)
+    if !head.is_cross_repository {
+        git::set_upstream(&worktree_path, &pr_branch, &git_ref)?;
+    }
 
     // Save state
-    let state = WorktreeState::new(worktree_path.clone(), git_root.clone(), pr_branch.clone());
-    state.save()?;
+    let mut state =
+        WorktreeState::new(worktree_path.clone(), git_root.clone(), pr_branch.clone());
+    state.pr_number = Some(pr_number);
+    super::save_state(&state, &options)?;
+
+    // Bring over local-only files such as .env before hooks that may rely on them
+    super::copy_gitignored(&config, &git_root, &worktree_path);
 
     // Execute hooks
     let all_hooks = config.get_hooks(repo_config);
-    if let Err(e) = hooks::execute_hooks(&all_hooks, &git_root, &worktree_path) {
+    let hook_env = config.get_hook_env(repo_config);
+    let parallel = config.default.parallel_hooks;
+    let result = hooks::execute_hooks(&all_hooks, &git_root, &worktree_path, &hook_env, parallel);
+    if let Err(e) = result {
         eprintln!("Warning: Hook failed: {}", e);
     }
 
+    // Announce the review on the PR; the worktree exists already, so only warn on failure
+    if let Some(comment) = comment {
+        if let Err(e) = post_review_comment(pr_number, &comment, &config, &git_root) {
+            eprintln!("Warning: Could not comment on PR #{}: {:#}", pr_number, e);
+        }
+    }
+
     // Output the worktree path
     eprintln!("Created worktree: {}", crate::state::display_path(&worktree_path));
     eprintln!("Branch: {} (PR #{})", pr_branch, pr_number);
-    println!("{}", worktree_path.display());
+    eprintln!(
+        "URL: {}",
+        git::pr_url(&github_repo.owner, &github_repo.repo, pr_number, &github_repo.host)
+    );
+    let no_cd = options.skips_cd(&config, repo_config);
+    super::print_created(&worktree_path, &pr_branch, &options, no_cd);
 
     Ok(())
 }
 
+/// Create the worktree on an existing local branch instead of creating it again.
+/// git refuses a branch that is checked out elsewhere, so say where.
+fn add_on_local_branch(
+    worktree_path: &Path,
+    branch: &str,
+    git_ref: &str,
+    git_root: &Path,
+) -> Result<()> {
+    if let Some(path) = git::find_worktree_for_branch(branch, git_root)? {
+        bail!(
+            "Branch '{}' already exists and is checked out at {}. \
+            Switch that checkout to another branch first.",
+            branch,
+            path.display()
+        );
+    }
+
+    eprintln!("Using existing local branch '{}'", branch);
+    if git::rev_parse(branch, git_root)? != git::rev_parse(git_ref, git_root)? {
+        eprintln!(
+            "Note: it differs from {}; `gj status` shows how far ahead or behind it is",
+            git_ref
+        );
+    }
+    git::worktree_add_at_ref(worktree_path, branch)
+}
+
+/// State of an existing PR worktree to replace, refusing to discard uncommitted changes
+fn stale_worktree(worktree_path: &Path, force: bool) -> Result<WorktreeState> {
+    let state = WorktreeState::load(worktree_path)?.with_context(|| {
+        format!(
+            "{} is not managed by gj, so it cannot be recreated",
+            worktree_path.display()
+        )
+    })?;
+
+    if !force && git::has_uncommitted_changes(worktree_path)? {
+        bail!(
+            "Worktree {} has uncommitted changes. Use --force to discard them.",
+            crate::state::display_path(worktree_path)
+        );
+    }
+
+    Ok(state)
+}
+
+/// Refuse to recreate a worktree whose branch has commits origin never had, since
+/// recreating it deletes the branch. Commits a force-push replaced were on origin before,
+/// so they are found in the remote-tracking ref's history rather than in the new head.
+fn check_no_local_commits(
+    state: &WorktreeState,
+    git_ref: &str,
+    previous_head: Option<String>,
+    git_root: &Path,
+) -> Result<()> {
+    let mut known = git::ref_history(&format!("refs/remotes/{}", git_ref), git_root)?;
+    known.extend(previous_head);
+    if known.is_empty() {
+        known.push(git_ref.to_string());
+    }
+
+    let unpushed = git::count_commits_not_on(&state.branch, &known, git_root)?;
+    if unpushed > 0 {
+        bail!(
+            "Branch '{}' has {} commit(s) that were never on {}. Push them first, \
+            or use --force to discard them.",
+            state.branch,
+            unpushed,
+            git_ref
+        );
+    }
+    Ok(())
+}
+
+/// Default comment for `gj pr --comment` when neither text nor `default.pr_comment` is given
+const DEFAULT_PR_COMMENT: &str = "Reviewing locally";
+
+/// Post the `--comment` text (or the configured template) on the PR
+fn post_review_comment(pr_number: u32, text: &str, config: &Config, git_root: &Path) -> Result<()> {
+    if !git::is_gh_available() {
+        return Err(GjError::GhNotInstalled.into());
+    }
+
+    let template = match text.trim() {
+        "" => config
+            .default
+            .pr_comment
+            .as_deref()
+            .unwrap_or(DEFAULT_PR_COMMENT),
+        text => text,
+    };
+    let body = if template.contains("{user}") {
+        render_comment(template, &git::get_gh_user()?)
+    } else {
+        template.to_string()
+    };
+
+    git::comment_on_pr(pr_number, &body, git_root)?;
+    eprintln!("Commented on PR #{}: {}", pr_number, body);
+    Ok(())
+}
+
+/// Substitute `{user}` in a comment template
+fn render_comment(template: &str, user: &str) -> String {
+    template.replace("{user}", user)
+}
+
+/// Interactively select one of the open PRs
+fn select_pr() -> Result<u32> {
+    let prs = git::list_open_prs()?;
+
+    if prs.is_empty() {
+        bail!("No open PRs found.");
+    }
+
+    let options: Vec<String> = prs.iter().map(format_pr_option).collect();
+
+    let selection = inquire::Select::new("Select PR:", options.clone())
+        .prompt()
+        .context("Failed to get selection")?;
+
+    let selected_index = options.iter().position(|o| *o == selection).unwrap();
+
+    Ok(prs[selected_index].number)
+}
+
+/// Execute the hidden `gj __complete_prs` command used by shell completion.
+/// Prints `number<TAB>title` per open PR, and nothing if gh is missing or fails.
+pub fn complete() -> Result<()> {
+    if let Ok(prs) = git::list_open_prs() {
+        for pr in &prs {
+            println!("{}", format_pr_completion(pr));
+        }
+    }
+    Ok(())
+}
+
+/// Format a PR as a completion candidate, keeping it on a single line
+fn format_pr_completion(pr: &git::PrSummary) -> String {
+    let title: String = pr
+        .title
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect();
+    format!("{}\t{}", pr.number, title)
+}
+
+/// Format a PR as a selection option
+fn format_pr_option(pr: &git::PrSummary) -> String {
+    format!("#{} {} ({})", pr.number, pr.title, pr.head_ref_name)
+}
+
 /// Generate worktree name for a PR
 fn pr_worktree_name(pr_number: u32) -> String {
     format!("pr-{}", pr_number)
@@ -77,6 +355,35 @@ fn pr_worktree_name(pr_number: u32) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_format_pr_option() {
+        let pr = git::PrSummary {
+            number: 42,
+            title: "Fix bug".to_string(),
+            head_ref_name: "fix-bug".to_string(),
+        };
+        assert_eq!(format_pr_option(&pr), "#42 Fix bug (fix-bug)");
+    }
+
+    #[test]
+    fn test_render_comment() {
+        assert_eq!(
+            render_comment("{user} is reviewing locally", "mirakui"),
+            "mirakui is reviewing locally"
+        );
+        assert_eq!(render_comment("Reviewing locally", "mirakui"), "Reviewing locally");
+    }
+
+    #[test]
+    fn test_format_pr_completion() {
+        let pr = git::PrSummary {
+            number: 42,
+            title: "Fix\tbug\nagain".to_string(),
+            head_ref_name: "fix-bug".to_string(),
+        };
+        assert_eq!(format_pr_completion(&pr), "42\tFix bug again");
+    }
+
     #[test]
     fn test_pr_worktree_name_single_digit() {
         assert_eq!(pr_worktree_name(1), "pr-1");
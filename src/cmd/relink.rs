@@ -0,0 +1,155 @@
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::git;
+use crate::state::{self, WorktreeState};
+
+/// Execute `gj relink`: point the states of a moved origin repository's worktrees at its
+/// new location. Without paths, the current repository is the new location and its
+/// worktrees are found through git.
+pub fn run(old: Option<PathBuf>, new: Option<PathBuf>, dry_run: bool) -> Result<()> {
+    let (new, states) = match (old, new) {
+        (Some(old), Some(new)) => {
+            let old = std::path::absolute(&old)
+                .with_context(|| format!("Invalid path: {}", old.display()))?;
+            let new = new
+                .canonicalize()
+                .with_context(|| format!("Repository not found: {}", new.display()))?;
+            if !new.join(".git").is_dir() {
+                bail!("Not the root of a git repository: {}", new.display());
+            }
+            let states = states_with_origin(state::list_all_states()?, &old);
+            (new, states)
+        }
+        _ => {
+            let root = git::get_repo_root()?;
+            let new = git::main_repo_root(&root).unwrap_or(root);
+            let worktrees: Vec<PathBuf> = git::list_worktrees(&new)?
+                .into_iter()
+                .map(|w| w.path)
+                .collect();
+            let states = moved_states(state::list_all_states()?, &new, &worktrees);
+            (new, states)
+        }
+    };
+
+    let states: Vec<WorktreeState> = states
+        .into_iter()
+        .filter(|s| s.origin_repo != new)
+        .collect();
+    if states.is_empty() {
+        eprintln!(
+            "No worktree states to relink to {}",
+            state::display_path(&new)
+        );
+        return Ok(());
+    }
+
+    let verb = if dry_run { "Would relink" } else { "Relinked" };
+    for mut state in states.clone() {
+        let old = std::mem::replace(&mut state.origin_repo, new.clone());
+        if !dry_run {
+            state.save()?;
+        }
+        eprintln!(
+            "{} {}: {} -> {}",
+            verb,
+            state::display_path(&state.worktree_path),
+            state::display_path(&old),
+            state::display_path(&new)
+        );
+    }
+    if dry_run {
+        return Ok(());
+    }
+
+    // The worktrees' `.git` files still point into the old location
+    let paths: Vec<&Path> = states
+        .iter()
+        .map(|s| s.worktree_path.as_path())
+        .filter(|path| path.is_dir())
+        .collect();
+    if !paths.is_empty() {
+        if let Err(e) = git::worktree_repair(&paths, &new) {
+            eprintln!(
+                "Warning: {:#}. Run `git worktree repair` in {}",
+                e,
+                new.display()
+            );
+        }
+    }
+
+    // A registered repository's path is configuration, not state; point the user at it
+    if let Ok(config) = Config::load() {
+        for (name, repo) in &config.repos {
+            let path = repo.expanded_path();
+            if states.iter().any(|s| s.origin_repo == path) {
+                let key = format!("repos.{}.path", name);
+                eprintln!("Note: {} is still the old path; to update it, run:", key);
+                eprintln!("  gj config set {} {}", key, new.display());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// States whose recorded origin repository is `old`
+fn states_with_origin(states: Vec<WorktreeState>, old: &Path) -> Vec<WorktreeState> {
+    states
+        .into_iter()
+        .filter(|s| s.origin_repo == old)
+        .collect()
+}
+
+/// States of `worktrees` (as listed by the repository at `repo`) recording another origin
+fn moved_states(
+    states: Vec<WorktreeState>,
+    repo: &Path,
+    worktrees: &[PathBuf],
+) -> Vec<WorktreeState> {
+    states
+        .into_iter()
+        .filter(|s| s.origin_repo != repo && worktrees.contains(&s.worktree_path))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(worktree: &str, origin: &str) -> WorktreeState {
+        WorktreeState::new(
+            PathBuf::from(worktree),
+            PathBuf::from(origin),
+            "branch".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_states_with_origin() {
+        let states = vec![state("/wt/a", "/old"), state("/wt/b", "/other")];
+        let selected = states_with_origin(states, Path::new("/old"));
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].worktree_path, PathBuf::from("/wt/a"));
+    }
+
+    #[test]
+    fn test_moved_states() {
+        let states = vec![
+            state("/wt/a", "/old"),
+            state("/wt/b", "/new"),
+            state("/wt/c", "/elsewhere"),
+        ];
+        let worktrees = vec![
+            PathBuf::from("/new"),
+            PathBuf::from("/wt/a"),
+            PathBuf::from("/wt/b"),
+        ];
+
+        let moved = moved_states(states, Path::new("/new"), &worktrees);
+        assert_eq!(moved.len(), 1);
+        assert_eq!(moved[0].origin_repo, PathBuf::from("/old"));
+    }
+}
@@ -1,18 +1,72 @@
 use anyhow::{bail, Result};
 
 const SHELL_FUNCTION: &str = r#"function gj() {
-  local output
-  output=$(command gj "$@")
-  local exit_code=$?
+  # Commands that print a directory without wanting a cd, or (`config edit`) run an
+  # editor that needs stdout to be the terminal. Global flags (-v, --verbose) take no
+  # value, so the subcommand is the first word that is not a flag.
+  local arg subcommand=""
+  for arg in "$@"; do
+    case "$arg" in
+      -*) ;;
+      *) subcommand="$arg"; break ;;
+    esac
+  done
+  case "$subcommand" in
+    base-dir|config) command gj "$@"; return ;;
+  esac
+
+  local output exit_code after_cd_file
+  after_cd_file=$(mktemp "${TMPDIR:-/tmp}/gj-after-cd.XXXXXX" 2>/dev/null) || after_cd_file=""
+  output=$(GJ_AFTER_CD_FILE="$after_cd_file" command gj "$@")
+  exit_code=$?
 
   if [[ $exit_code -eq 0 && -d "$output" ]]; then
-    cd "$output"
-    echo "You are now in: ${output/#$HOME/~}"
+    if cd -- "$output"; then
+      local display="$output"
+      case "$output" in
+        "$HOME"/*) display="~${output#"$HOME"}" ;;
+      esac
+      printf 'You are now in: %s\n' "$display"
+      # Commands gj left to run after the cd (e.g. after_exit hooks), in a separate
+      # sh so they cannot change or end this shell
+      [[ -s "$after_cd_file" ]] && sh "$after_cd_file"
+    else
+      exit_code=1
+    fi
+  elif [[ -n "$output" ]]; then
+    printf '%s\n' "$output"
+  fi
+
+  [[ -n "$after_cd_file" ]] && rm -f -- "$after_cd_file"
+  return $exit_code
+}
+"#;
+
+/// Completes `gj pr <TAB>` with open PR numbers from `gj __complete_prs`, and
+/// falls back to file names elsewhere
+const BASH_COMPLETION: &str = r#"_gj_complete() {
+  local cur="${COMP_WORDS[COMP_CWORD]}"
+  COMPREPLY=()
+  if [[ $COMP_CWORD -ge 2 && "${COMP_WORDS[COMP_CWORD-1]}" == pr ]]; then
+    COMPREPLY=($(compgen -W "$(command gj __complete_prs 2>/dev/null | cut -f1)" -- "$cur"))
+  fi
+}
+complete -o default -F _gj_complete gj
+"#;
+
+/// Same as `BASH_COMPLETION` for zsh, showing PR titles as descriptions. Only
+/// registered when the completion system is loaded (`compinit`).
+const ZSH_COMPLETION: &str = r#"_gj_complete() {
+  if (( CURRENT >= 3 )) && [[ "${words[CURRENT-1]}" == pr ]]; then
+    local -a prs
+    prs=(${(f)"$(command gj __complete_prs 2>/dev/null)"})
+    prs=(${prs/$'\t'/:})
+    _describe 'pull request' prs
   else
-    echo "$output"
-    return $exit_code
+    _files
   fi
 }
+(( $+functions[compdef] )) && compdef _gj_complete gj
 "#;
 
 /// Execute the `gj shell-init` command
@@ -26,18 +80,32 @@ pub fn run(shell: &str) -> Result<()> {
 }
 
 /// Returns the shell initialization script for zsh
-fn zsh_init_script() -> &'static str {
-    SHELL_FUNCTION
+fn zsh_init_script() -> String {
+    format!("{}{}", SHELL_FUNCTION, ZSH_COMPLETION)
 }
 
 /// Returns the shell initialization script for bash
-fn bash_init_script() -> &'static str {
-    SHELL_FUNCTION
+fn bash_init_script() -> String {
+    format!("{}{}", SHELL_FUNCTION, BASH_COMPLETION)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(unix)]
+    use std::os::unix::fs::PermissionsExt;
+
+    /// Put a stub `gj` running `script` in a `bin` directory of `temp_dir`,
+    /// returning a PATH that finds it first
+    #[cfg(unix)]
+    fn stub_gj(temp_dir: &tempfile::TempDir, script: &str) -> String {
+        let bin_dir = temp_dir.path().join("bin");
+        std::fs::create_dir(&bin_dir).unwrap();
+        let stub = bin_dir.join("gj");
+        std::fs::write(&stub, script).unwrap();
+        std::fs::set_permissions(&stub, std::fs::Permissions::from_mode(0o755)).unwrap();
+        format!("{}:/usr/bin:/bin", bin_dir.display())
+    }
 
     #[test]
     fn test_zsh_init_script_contains_function_definition() {
@@ -48,7 +116,123 @@ mod tests {
     #[test]
     fn test_zsh_init_script_contains_cd_logic() {
         let script = zsh_init_script();
-        assert!(script.contains("cd \"$output\""));
+        assert!(script.contains("cd -- \"$output\""));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_shell_function_cds_into_path_with_spaces() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let target = temp_dir.path().join("my worktrees").join("-dash dir");
+        std::fs::create_dir_all(&target).unwrap();
+
+        // Stub `gj` binary that prints the target path like `gj cd` would
+        let path = stub_gj(&temp_dir, "#!/bin/sh\nprintf '%s\\n' \"$GJ_TARGET\"\n");
+
+        let script = format!("{}\ngj cd x >/dev/null && pwd", bash_init_script());
+        let output = std::process::Command::new("bash")
+            .arg("-c")
+            .arg(&script)
+            .env("PATH", &path)
+            .env("GJ_TARGET", &target)
+            .output()
+            .expect("Failed to run bash");
+
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout).trim_end(),
+            target.to_string_lossy()
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_shell_function_runs_after_cd_commands_in_target() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let target = temp_dir.path().join("origin");
+        std::fs::create_dir(&target).unwrap();
+
+        // Stub `gj` that hands a command to the wrapper like `gj exit` with after_exit hooks
+        let path = stub_gj(
+            &temp_dir,
+            "#!/bin/sh\nprintf 'pwd > after.txt\\ncd /\\nexit 3\\n' > \"$GJ_AFTER_CD_FILE\"\n\
+            printf '%s\\n' \"$GJ_TARGET\"\n",
+        );
+
+        // The hook's `cd` and `exit` must not reach the calling shell
+        let script = format!("{}\ngj exit >/dev/null && pwd", bash_init_script());
+        let output = std::process::Command::new("bash")
+            .arg("-c")
+            .arg(&script)
+            .env("PATH", &path)
+            .env("GJ_TARGET", &target)
+            .env("TMPDIR", temp_dir.path())
+            .output()
+            .expect("Failed to run bash");
+
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        let after = std::fs::read_to_string(target.join("after.txt")).unwrap();
+        assert_eq!(after.trim_end(), target.to_string_lossy());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout).trim_end(),
+            target.to_string_lossy()
+        );
+        // The temporary file is cleaned up
+        assert!(!std::fs::read_dir(temp_dir.path())
+            .unwrap()
+            .any(|e| e.unwrap().file_name().to_string_lossy().starts_with("gj-after-cd")));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_shell_function_does_not_cd_for_base_dir() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+
+        let path = stub_gj(&temp_dir, "#!/bin/sh\nprintf '%s\\n' \"$GJ_TARGET\"\n");
+
+        let script = format!("{}\ncd / && gj base-dir && pwd", bash_init_script());
+        let output = std::process::Command::new("bash")
+            .arg("-c")
+            .arg(&script)
+            .env("PATH", &path)
+            .env("GJ_TARGET", temp_dir.path())
+            .output()
+            .expect("Failed to run bash");
+
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert_eq!(stdout, format!("{}\n/\n", temp_dir.path().display()));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_shell_function_does_not_capture_config() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+
+        // Stub `gj` reporting whether the wrapper captured its output
+        let path = stub_gj(
+            &temp_dir,
+            "#!/bin/sh
+if [ -n \"${GJ_AFTER_CD_FILE+set}\" ]; then echo captured; else echo direct; fi\n",
+        );
+
+        let script = format!(
+            "{}\ngj config edit && gj -v config edit && gj --verbose config edit && gj -v list",
+            bash_init_script()
+        );
+        let output = std::process::Command::new("bash")
+            .arg("-c")
+            .arg(&script)
+            .env("PATH", &path)
+            .env("TMPDIR", temp_dir.path())
+            .output()
+            .expect("Failed to run bash");
+
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "direct\ndirect\ndirect\ncaptured\n"
+        );
     }
 
     #[test]
@@ -65,9 +249,40 @@ mod tests {
     }
 
     #[test]
-    fn test_zsh_and_bash_scripts_are_identical() {
-        // Both shells use the same script
-        assert_eq!(zsh_init_script(), bash_init_script());
+    fn test_zsh_and_bash_scripts_share_the_wrapper() {
+        // Both shells use the same wrapper, with their own completion
+        assert!(zsh_init_script().starts_with(SHELL_FUNCTION));
+        assert!(bash_init_script().starts_with(SHELL_FUNCTION));
+        assert!(zsh_init_script().contains("compdef _gj_complete gj"));
+        assert!(bash_init_script().contains("complete -o default -F _gj_complete gj"));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_bash_completion_offers_open_prs() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+
+        // Stub `gj` answering `__complete_prs` like it would with two open PRs
+        let path = stub_gj(
+            &temp_dir,
+            "#!/bin/sh\n[ \"$1\" = __complete_prs ] && printf '12\\tFix it\\n34\\tAdd it\\n'\n",
+        );
+
+        let script = format!(
+            "{}\nCOMP_WORDS=(gj pr ''); COMP_CWORD=2; _gj_complete; echo \"${{COMPREPLY[*]}}\"\n\
+            COMP_WORDS=(gj -v pr 3); COMP_CWORD=3; _gj_complete; echo \"${{COMPREPLY[*]}}\"\n\
+            COMP_WORDS=(gj cd ''); COMP_CWORD=2; _gj_complete; echo \"${{#COMPREPLY[@]}}\"",
+            bash_init_script()
+        );
+        let output = std::process::Command::new("bash")
+            .arg("-c")
+            .arg(&script)
+            .env("PATH", &path)
+            .output()
+            .expect("Failed to run bash");
+
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "12 34\n34\n0\n");
     }
 
     #[test]
@@ -0,0 +1,184 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::history::{self, Event, EventKind};
+use crate::state;
+
+/// Usage figures aggregated from the activity log
+#[derive(Debug, Default, PartialEq)]
+struct Summary {
+    created: usize,
+    first_created: Option<DateTime<Utc>>,
+    /// Creations per origin repository, most first
+    per_repo: Vec<(PathBuf, usize)>,
+    /// Lifetimes of worktrees with both a create and an exit event
+    lifetimes: Vec<Duration>,
+}
+
+impl Summary {
+    /// Creations per week since the first logged creation (at least one week)
+    fn per_week(&self, now: DateTime<Utc>) -> Option<f64> {
+        let first = self.first_created?;
+        let weeks = (now - first).num_seconds() as f64 / Duration::weeks(1).num_seconds() as f64;
+        Some(self.created as f64 / weeks.max(1.0))
+    }
+
+    /// Median lifetime of exited worktrees
+    fn median_lifetime(&self) -> Option<Duration> {
+        let mut lifetimes = self.lifetimes.clone();
+        lifetimes.sort();
+        let mid = lifetimes.len() / 2;
+        match lifetimes.len() {
+            0 => None,
+            n if n % 2 == 1 => Some(lifetimes[mid]),
+            _ => Some((lifetimes[mid - 1] + lifetimes[mid]) / 2),
+        }
+    }
+}
+
+/// Execute the `gj stats` command
+pub fn run() -> Result<()> {
+    let events = history::read_events()?;
+    let open = state::list_all_states()?.len();
+    let summary = summarize(&events);
+    let now = Utc::now();
+
+    if summary.created == 0 {
+        println!("No worktree activity recorded yet.");
+        println!("Currently open:  {}", open);
+        return Ok(());
+    }
+
+    match (summary.per_week(now), summary.first_created) {
+        (Some(per_week), Some(first)) => println!(
+            "Created:         {} ({:.1} per week since {})",
+            summary.created,
+            per_week,
+            first.format("%Y-%m-%d")
+        ),
+        _ => println!("Created:         {}", summary.created),
+    }
+    match summary.median_lifetime() {
+        Some(median) => println!(
+            "Median lifetime: {} (of {} removed)",
+            format_duration(median),
+            summary.lifetimes.len()
+        ),
+        None => println!("Median lifetime: - (no worktree removed yet)"),
+    }
+    println!("Currently open:  {}", open);
+
+    println!("\nCreated per repository:");
+    for (repo, count) in &summary.per_repo {
+        println!("  {:>4}  {}", count, state::display_path(repo));
+    }
+
+    Ok(())
+}
+
+/// Aggregate log events; an exit without a logged create (a partial log) is ignored
+fn summarize(events: &[Event]) -> Summary {
+    let mut summary = Summary::default();
+    let mut per_repo: HashMap<&PathBuf, usize> = HashMap::new();
+    let mut open: HashMap<&PathBuf, DateTime<Utc>> = HashMap::new();
+
+    for event in events {
+        match event.event {
+            EventKind::Create => {
+                summary.created += 1;
+                summary.first_created = Some(match summary.first_created {
+                    Some(first) => first.min(event.at),
+                    None => event.at,
+                });
+                *per_repo.entry(&event.repo).or_default() += 1;
+                open.insert(&event.worktree_path, event.at);
+            }
+            EventKind::Exit => {
+                if let Some(created) = open.remove(&event.worktree_path) {
+                    summary.lifetimes.push(event.at - created);
+                }
+            }
+        }
+    }
+
+    let mut per_repo: Vec<_> = per_repo
+        .into_iter()
+        .map(|(repo, count)| (repo.clone(), count))
+        .collect();
+    per_repo.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    summary.per_repo = per_repo;
+
+    summary
+}
+
+/// Format a duration with its two largest units, e.g. "3d 4h" or "25m"
+fn format_duration(duration: Duration) -> String {
+    let days = duration.num_days();
+    let hours = duration.num_hours() % 24;
+    let minutes = duration.num_minutes() % 60;
+
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(kind: EventKind, hours: i64, repo: &str, worktree: &str) -> Event {
+        Event {
+            event: kind,
+            at: DateTime::parse_from_rfc3339("2026-10-01T00:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc)
+                + Duration::hours(hours),
+            repo: PathBuf::from(repo),
+            branch: "b".to_string(),
+            worktree_path: PathBuf::from(worktree),
+        }
+    }
+
+    #[test]
+    fn test_summarize() {
+        let events = vec![
+            // Exit of a worktree created before the log started
+            event(EventKind::Exit, 0, "/a", "/wt/old"),
+            event(EventKind::Create, 0, "/a", "/wt/1"),
+            event(EventKind::Create, 1, "/b", "/wt/2"),
+            event(EventKind::Create, 2, "/a", "/wt/3"),
+            event(EventKind::Exit, 10, "/a", "/wt/1"),
+            event(EventKind::Exit, 5, "/b", "/wt/2"),
+            event(EventKind::Exit, 50, "/a", "/wt/3"),
+        ];
+
+        let summary = summarize(&events);
+        assert_eq!(summary.created, 3);
+        assert_eq!(
+            summary.per_repo,
+            vec![(PathBuf::from("/a"), 2), (PathBuf::from("/b"), 1)]
+        );
+        assert_eq!(summary.lifetimes.len(), 3);
+        assert_eq!(summary.median_lifetime(), Some(Duration::hours(10)));
+
+        let two_weeks_later = summary.first_created.unwrap() + Duration::weeks(2);
+        assert_eq!(summary.per_week(two_weeks_later), Some(1.5));
+        // Less than a week of history counts as one week
+        assert_eq!(summary.per_week(summary.first_created.unwrap()), Some(3.0));
+
+        assert_eq!(summarize(&[]), Summary::default());
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::minutes(25)), "25m");
+        assert_eq!(format_duration(Duration::minutes(185)), "3h 5m");
+        assert_eq!(format_duration(Duration::hours(76)), "3d 4h");
+    }
+}
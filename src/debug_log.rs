@@ -0,0 +1,152 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+use std::time::Instant;
+
+/// Environment variable enabling the debug log (`GJ_DEBUG=1`)
+pub const DEBUG_ENV: &str = "GJ_DEBUG";
+
+/// Whether the debug log is enabled, read once per process
+static ENABLED: OnceLock<bool> = OnceLock::new();
+
+/// What a debug log line describes
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+enum Record {
+    /// gj was started with these arguments
+    Start { args: Vec<String> },
+    /// A spawned process finished; `status` is None if it could not be run or was killed
+    Process {
+        program: String,
+        args: Vec<String>,
+        dir: Option<PathBuf>,
+        status: Option<i32>,
+        duration_ms: u128,
+    },
+    /// gj finished, with the error it reports if it failed
+    Finish {
+        error: Option<String>,
+        duration_ms: u128,
+    },
+}
+
+/// One line of the debug log
+#[derive(Debug, Serialize)]
+struct Line {
+    at: DateTime<Utc>,
+    pid: u32,
+    #[serde(flatten)]
+    record: Record,
+}
+
+/// Whether `GJ_DEBUG` is set to something other than empty or `0`
+pub fn enabled() -> bool {
+    *ENABLED.get_or_init(|| {
+        std::env::var(DEBUG_ENV).is_ok_and(|value| !value.is_empty() && value != "0")
+    })
+}
+
+/// Get the debug log path (~/.gj/debug.log)
+pub fn log_path() -> Result<PathBuf> {
+    let home_dir = dirs::home_dir().context("Could not determine home directory")?;
+    Ok(home_dir.join(".gj").join("debug.log"))
+}
+
+/// Log the arguments gj was started with
+pub fn start() {
+    write(Record::Start {
+        args: std::env::args().skip(1).collect(),
+    });
+}
+
+/// Log the outcome of a gj run that began at `started`
+pub fn finish(result: &Result<()>, started: Instant) {
+    write(Record::Finish {
+        error: result.as_ref().err().map(|e| format!("{:#}", e)),
+        duration_ms: started.elapsed().as_millis(),
+    });
+}
+
+/// Log a spawned process that began at `started`
+pub fn process(command: &Command, started: Instant, status: Option<i32>) {
+    if !enabled() {
+        return;
+    }
+    write(Record::Process {
+        program: command.get_program().to_string_lossy().into_owned(),
+        args: command
+            .get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect(),
+        dir: command.get_current_dir().map(Path::to_path_buf),
+        status,
+        duration_ms: started.elapsed().as_millis(),
+    });
+}
+
+/// Append a record if the log is enabled. Troubleshooting must not break gj, so
+/// failures to write are ignored.
+fn write(record: Record) {
+    if !enabled() {
+        return;
+    }
+    if let Ok(path) = log_path() {
+        let _ = append(&path, &format_line(record));
+    }
+}
+
+/// Format a record as one JSON line
+fn format_line(record: Record) -> String {
+    let line = Line {
+        at: Utc::now(),
+        pid: std::process::id(),
+        record,
+    };
+    serde_json::to_string(&line).unwrap_or_default()
+}
+
+/// Append a line to the log, creating it if needed
+fn append(path: &Path, line: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_line() {
+        let line = format_line(Record::Process {
+            program: "git".to_string(),
+            args: vec!["worktree".to_string(), "list".to_string()],
+            dir: Some(PathBuf::from("/repo")),
+            status: Some(0),
+            duration_ms: 12,
+        });
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(value["event"], "process");
+        assert_eq!(value["program"], "git");
+        assert_eq!(value["args"], serde_json::json!(["worktree", "list"]));
+        assert_eq!(value["dir"], "/repo");
+        assert_eq!(value["status"], 0);
+        assert_eq!(value["duration_ms"], 12);
+        assert_eq!(value["pid"], std::process::id());
+        assert!(value["at"].is_string());
+
+        let line = format_line(Record::Finish {
+            error: Some("Not in a git repository".to_string()),
+            duration_ms: 3,
+        });
+        assert!(line.contains(r#""event":"finish","error":"Not in a git repository""#));
+    }
+}
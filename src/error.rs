@@ -0,0 +1,161 @@
+use std::fmt;
+use std::io::IsTerminal;
+use std::path::PathBuf;
+
+/// Known error categories that come with a hint for the user
+#[derive(Debug)]
+pub enum GjError {
+    /// The configuration file does not exist
+    ConfigNotFound(PathBuf),
+    /// The current directory is not inside a git repository
+    NotInRepo,
+    /// The current directory is not a gj-managed worktree
+    NotInWorktree,
+    /// gh CLI is not installed
+    GhNotInstalled,
+    /// gh CLI is installed but not authenticated
+    GhNotAuthenticated,
+    /// A worktree's recorded origin repository no longer exists (e.g. it was moved)
+    OriginMissing(PathBuf),
+}
+
+impl GjError {
+    /// Suggested next step for the user
+    pub fn hint(&self) -> &'static str {
+        match self {
+            GjError::ConfigNotFound(_) => "Run `gj init` to create a configuration file.",
+            GjError::NotInRepo => "Run this command inside a git repository.",
+            GjError::NotInWorktree => {
+                "Run this inside a worktree created by gj, or use `gj list` to find one."
+            }
+            GjError::GhNotInstalled => {
+                "Install gh CLI from https://cli.github.com/ (or set GITHUB_TOKEN for `gj pr <number>`)"
+            }
+            GjError::GhNotAuthenticated => "Run `gh auth login` and try again.",
+            GjError::OriginMissing(_) => {
+                "If the repository was moved, run `gj relink` inside it (or `gj relink <old> <new>`)."
+            }
+        }
+    }
+}
+
+impl fmt::Display for GjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GjError::ConfigNotFound(path) => {
+                write!(f, "Configuration file not found at {}", path.display())
+            }
+            GjError::NotInRepo => write!(f, "Not in a git repository"),
+            GjError::NotInWorktree => write!(f, "Not in a gj-managed worktree"),
+            GjError::GhNotInstalled => write!(f, "gh CLI is not installed"),
+            GjError::GhNotAuthenticated => write!(f, "gh CLI is not authenticated"),
+            GjError::OriginMissing(path) => {
+                write!(f, "Origin repository not found at {}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for GjError {}
+
+/// Print an error to stderr with a red `error:` prefix and a dimmed hint for known categories
+pub fn report(err: &anyhow::Error) {
+    let color = use_color(&std::io::stderr());
+    eprint!("{}", format_error(err, color));
+}
+
+/// Whether an error comes from the user cancelling an interactive prompt (Esc or Ctrl-C),
+/// which gj exits on quietly. Only prompts asked before gj has changed anything may pass
+/// the cancellation up; a prompt after side effects (such as keeping a conflicted merge)
+/// must handle it itself, so that nothing is left half done without a message.
+pub fn is_cancelled(err: &anyhow::Error) -> bool {
+    err.chain().any(|e| {
+        matches!(
+            e.downcast_ref::<inquire::InquireError>(),
+            Some(
+                inquire::InquireError::OperationCanceled
+                    | inquire::InquireError::OperationInterrupted
+            )
+        )
+    })
+}
+
+/// Format an error and its hint for display
+fn format_error(err: &anyhow::Error, color: bool) -> String {
+    let (red, dim, reset) = if color {
+        ("\x1b[1;31m", "\x1b[2m", "\x1b[0m")
+    } else {
+        ("", "", "")
+    };
+
+    let mut output = format!("{}error:{} {:#}\n", red, reset, err);
+
+    if let Some(gj_error) = err.chain().find_map(|e| e.downcast_ref::<GjError>()) {
+        output.push_str(&format!("{}hint: {}{}\n", dim, gj_error.hint(), reset));
+    }
+
+    output
+}
+
+/// Whether to color output: the stream is a terminal and `NO_COLOR` is not set
+pub fn use_color(stream: &impl IsTerminal) -> bool {
+    let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    !no_color && stream.is_terminal()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_format_error_plain() {
+        let err = anyhow::anyhow!("Something went wrong");
+        assert_eq!(format_error(&err, false), "error: Something went wrong\n");
+    }
+
+    #[test]
+    fn test_format_error_with_hint() {
+        let err = anyhow::Error::new(GjError::ConfigNotFound(PathBuf::from(
+            "/home/u/.gj/config.toml",
+        )));
+        assert_eq!(
+            format_error(&err, false),
+            "error: Configuration file not found at /home/u/.gj/config.toml\n\
+             hint: Run `gj init` to create a configuration file.\n"
+        );
+    }
+
+    #[test]
+    fn test_format_error_finds_hint_through_context() {
+        let err = Err::<(), _>(GjError::NotInRepo)
+            .context("Must be run inside a git repository")
+            .unwrap_err();
+        let output = format_error(&err, false);
+        assert!(output
+            .starts_with("error: Must be run inside a git repository: Not in a git repository\n"));
+        assert!(output.ends_with("hint: Run this command inside a git repository.\n"));
+    }
+
+    #[test]
+    fn test_is_cancelled() {
+        let err = Err::<(), _>(inquire::InquireError::OperationInterrupted)
+            .context("Failed to get selection")
+            .unwrap_err();
+        assert!(is_cancelled(&err));
+
+        let err = Err::<(), _>(inquire::InquireError::NotTTY)
+            .context("Failed to get selection")
+            .unwrap_err();
+        assert!(!is_cancelled(&err));
+        assert!(!is_cancelled(&anyhow::anyhow!("Something went wrong")));
+    }
+
+    #[test]
+    fn test_format_error_colored() {
+        let err = anyhow::Error::new(GjError::GhNotInstalled);
+        let output = format_error(&err, true);
+        assert!(output.starts_with("\x1b[1;31merror:\x1b[0m gh CLI is not installed\n"));
+        assert!(output.contains("\x1b[2mhint: "));
+    }
+}
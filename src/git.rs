@@ -1,162 +1,449 @@
 use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, Output, Stdio};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Once, OnceLock};
+use std::time::Instant;
+
+use crate::error::GjError;
+
+/// Minimum supported git version (`git worktree remove` requires 2.17)
+pub const MIN_GIT_VERSION: (u32, u32) = (2, 17);
+
+/// Git binary configured via `default.git_path`
+static GIT_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// Ensures the git version check runs at most once per process
+static VERSION_CHECK: Once = Once::new();
+
+/// Whether spawned commands are echoed to stderr (`--verbose`)
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+
+/// Captured result of a git command
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GitOutput {
+    pub success: bool,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Executes git commands
+pub trait GitRunner {
+    /// Run git with `args`, in `dir` if given (otherwise the current directory)
+    fn run(&self, args: &[&str], dir: Option<&Path>) -> Result<GitOutput>;
+}
+
+/// Runs the configured git binary
+pub struct SystemGitRunner;
+
+impl GitRunner for SystemGitRunner {
+    fn run(&self, args: &[&str], dir: Option<&Path>) -> Result<GitOutput> {
+        let mut command = git_command();
+        command.args(args);
+        if let Some(dir) = dir {
+            command.current_dir(dir);
+        }
+
+        let started = Instant::now();
+        let output = traced(&mut command).output();
+        crate::debug_log::process(&command, started, output_code(&output));
+        let output = output.with_context(|| format!("Failed to execute git {}", args.join(" ")))?;
+
+        Ok(GitOutput {
+            success: output.status.success(),
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        })
+    }
+}
+
+thread_local! {
+    /// Runner overriding `SystemGitRunner` on the current thread (tests only)
+    static RUNNER: RefCell<Option<Rc<dyn GitRunner>>> = const { RefCell::new(None) };
+}
+
+/// Run `f` with `runner` executing all git commands on the current thread
+#[cfg(test)]
+pub fn with_runner<T>(runner: Rc<dyn GitRunner>, f: impl FnOnce() -> T) -> T {
+    RUNNER.with(|r| *r.borrow_mut() = Some(runner));
+    let result = f();
+    RUNNER.with(|r| *r.borrow_mut() = None);
+    result
+}
+
+/// Run a git command with the active runner
+fn git(args: &[&str], dir: Option<&Path>) -> Result<GitOutput> {
+    match RUNNER.with(|r| r.borrow().clone()) {
+        Some(runner) => runner.run(args, dir),
+        None => SystemGitRunner.run(args, dir),
+    }
+}
+
+/// Run a git command, failing with `error` and git's stderr if it does not succeed.
+/// Returns the trimmed stdout.
+fn git_checked(args: &[&str], dir: Option<&Path>, error: &str) -> Result<String> {
+    let output = git(args, dir)?;
+
+    if !output.success {
+        bail!("{}: {}", error, output.stderr.trim());
+    }
+
+    Ok(output.stdout.trim().to_string())
+}
+
+/// Echo every git, gh and hook command to stderr before running it
+pub fn set_verbose(verbose: bool) {
+    VERBOSE.store(verbose, Ordering::Relaxed);
+}
+
+/// Print `command` to stderr with `--verbose`, returning it for chaining
+pub fn traced(command: &mut Command) -> &mut Command {
+    if VERBOSE.load(Ordering::Relaxed) {
+        eprintln!("{}", format_command_line(command));
+    }
+    command
+}
+
+/// Format a command as a shell-like trace line
+/// Example: `+ git worktree add -b 'my branch' /wt  (in /repo)`
+fn format_command_line(command: &Command) -> String {
+    let mut line = String::from("+");
+    for part in std::iter::once(command.get_program()).chain(command.get_args()) {
+        line.push(' ');
+        line.push_str(&crate::shell::quote_for_display(&part.to_string_lossy()));
+    }
+    if let Some(dir) = command.get_current_dir() {
+        line.push_str(&format!("  (in {})", dir.display()));
+    }
+    line
+}
+
+/// Run gh CLI with `args`, in `dir` if given
+fn gh(args: &[&str], dir: Option<&Path>) -> std::io::Result<Output> {
+    let mut command = Command::new("gh");
+    command.args(args);
+    if let Some(dir) = dir {
+        command.current_dir(dir);
+    }
+    let started = Instant::now();
+    let output = traced(&mut command).output();
+    crate::debug_log::process(&command, started, output_code(&output));
+    output
+}
+
+/// Exit code of a finished process for the debug log (None if it could not run or was killed)
+fn output_code(output: &std::io::Result<Output>) -> Option<i32> {
+    output.as_ref().ok().and_then(|output| output.status.code())
+}
+
+/// Set the git binary used for all git commands (default: `git` on PATH)
+pub fn set_git_path(path: PathBuf) {
+    let _ = GIT_PATH.set(path);
+}
+
+/// Get the git binary used for all git commands
+fn git_path() -> &'static Path {
+    GIT_PATH
+        .get()
+        .map(|p| p.as_path())
+        .unwrap_or(Path::new("git"))
+}
+
+/// Environment variables that point git at a specific repository, as set inside git hooks
+/// or by some CI systems
+const REPO_ENV_VARS: &[&str] = &[
+    "GIT_DIR",
+    "GIT_WORK_TREE",
+    "GIT_INDEX_FILE",
+    "GIT_COMMON_DIR",
+    "GIT_OBJECT_DIRECTORY",
+    "GIT_ALTERNATE_OBJECT_DIRECTORIES",
+    "GIT_NAMESPACE",
+    "GIT_PREFIX",
+];
+
+/// Create a `Command` for the configured git binary
+pub fn git_command() -> Command {
+    VERSION_CHECK.call_once(warn_if_outdated);
+    let mut command = Command::new(git_path());
+    clear_repo_env(&mut command);
+    command
+}
+
+/// Remove `REPO_ENV_VARS` so git finds the repository from its working directory alone
+fn clear_repo_env(command: &mut Command) -> &mut Command {
+    for var in REPO_ENV_VARS {
+        command.env_remove(var);
+    }
+    command
+}
+
+/// Warn if the git version is older than `MIN_GIT_VERSION`
+fn warn_if_outdated() {
+    let Ok(version) = version() else {
+        // Spawning git will fail with a clearer error
+        return;
+    };
+
+    if let Some(parsed) = parse_git_version(&version) {
+        if parsed < MIN_GIT_VERSION {
+            eprintln!(
+                "Warning: {} is older than the minimum supported version {}.{}",
+                version, MIN_GIT_VERSION.0, MIN_GIT_VERSION.1
+            );
+        }
+    }
+}
+
+/// Parse the major and minor version from `git --version` output
+/// Example: "git version 2.39.3 (Apple Git-146)" -> (2, 39)
+pub fn parse_git_version(version: &str) -> Option<(u32, u32)> {
+    let number = version
+        .strip_prefix("git version ")?
+        .split_whitespace()
+        .next()?;
+    let mut parts = number.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}
+
+/// Host of GitHub repositories
+pub const GITHUB_HOST: &str = "github.com";
 
 /// GitHub repository information parsed from remote URL
 #[derive(Debug, Clone, PartialEq)]
 pub struct GitHubRepo {
+    pub host: String,
     pub owner: String,
     pub repo: String,
 }
 
 /// Get GitHub repository info from the origin remote URL
 pub fn get_github_repo_info() -> Result<GitHubRepo> {
-    let output = Command::new("git")
-        .args(["remote", "get-url", "origin"])
-        .output()
-        .context("Failed to execute git remote get-url")?;
+    let output = git(&["remote", "get-url", "origin"], None)?;
 
-    if !output.status.success() {
+    if !output.success {
         bail!("Failed to get origin remote URL. Is this a git repository with an origin remote?");
     }
 
-    let url = String::from_utf8(output.stdout)
-        .context("Invalid UTF-8 in git output")?
-        .trim()
-        .to_string();
-
-    parse_github_url(&url)
+    parse_github_url(output.stdout.trim())
 }
 
-/// Parse a GitHub URL (SSH or HTTPS) into owner and repo
+/// Parse a GitHub URL (SSH or HTTPS) into host, owner and repo.
+/// Any host is accepted, so GitHub Enterprise remotes keep their own host.
 fn parse_github_url(url: &str) -> Result<GitHubRepo> {
-    // SSH format: git@github.com:owner/repo.git
-    if let Some(rest) = url.strip_prefix("git@github.com:") {
-        let path = rest.strip_suffix(".git").unwrap_or(rest);
-        return parse_owner_repo(path);
-    }
+    let Some((host, path)) = split_remote_url(url) else {
+        bail!(
+            "Unsupported remote URL format: {}. Expected an SSH or HTTPS remote such as \
+            git@github.com:owner/repo.git.",
+            url
+        );
+    };
 
-    // HTTPS format: https://github.com/owner/repo.git
-    if let Some(rest) = url.strip_prefix("https://github.com/") {
-        let path = rest.strip_suffix(".git").unwrap_or(rest);
-        return parse_owner_repo(path);
-    }
+    let path = path.trim_end_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    parse_owner_repo(&host.to_ascii_lowercase(), path)
+}
 
-    // HTTP format: http://github.com/owner/repo.git
-    if let Some(rest) = url.strip_prefix("http://github.com/") {
-        let path = rest.strip_suffix(".git").unwrap_or(rest);
-        return parse_owner_repo(path);
+/// Split a remote URL into host and path. Handles `https://`, `http://` and `ssh://` URLs
+/// (dropping any user and port) and the scp-like SSH form `git@<host>:<path>`.
+fn split_remote_url(url: &str) -> Option<(&str, &str)> {
+    let (authority, path) = match url.split_once("://") {
+        Some(("https" | "http" | "ssh", rest)) => rest.split_once('/')?,
+        Some(_) => return None,
+        None => {
+            let (authority, path) = url.split_once(':')?;
+            // A local path such as ./repo:name is not a remote
+            if authority.contains('/') {
+                return None;
+            }
+            (authority, path)
+        }
+    };
+
+    let host = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let host = host.split(':').next().unwrap_or(host);
+    if host.is_empty() {
+        return None;
     }
-
-    bail!(
-        "Unsupported remote URL format: {}. Only GitHub repositories (github.com) are supported.",
-        url
-    )
+    Some((host, path))
 }
 
 /// Parse "owner/repo" string into GitHubRepo
-fn parse_owner_repo(path: &str) -> Result<GitHubRepo> {
+fn parse_owner_repo(host: &str, path: &str) -> Result<GitHubRepo> {
     let parts: Vec<&str> = path.split('/').collect();
     if parts.len() != 2 || parts[0].is_empty() || parts[1].is_empty() {
         bail!("Invalid GitHub repository path: {}", path);
     }
 
     Ok(GitHubRepo {
+        host: host.to_string(),
         owner: parts[0].to_string(),
         repo: parts[1].to_string(),
     })
 }
 
+/// Web URL of a pull request: `https://<host>/<owner>/<repo>/pull/<n>`, for github.com and
+/// GitHub Enterprise hosts alike
+pub fn pr_url(owner: &str, repo: &str, number: u32, host: &str) -> String {
+    format!("https://{}/{}/{}/pull/{}", host, owner, repo, number)
+}
+
+/// Summary of an open pull request as reported by gh CLI
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PrSummary {
+    pub number: u32,
+    pub title: String,
+    #[serde(rename = "headRefName")]
+    pub head_ref_name: String,
+}
+
 /// Get the root directory of the current git repository
 pub fn get_repo_root() -> Result<PathBuf> {
-    let output = Command::new("git")
-        .args(["rev-parse", "--show-toplevel"])
-        .output()
-        .context("Failed to execute git command")?;
+    let output = git(&["rev-parse", "--show-toplevel"], None)?;
 
-    if !output.status.success() {
-        bail!("Not in a git repository");
+    if !output.success {
+        return Err(GjError::NotInRepo.into());
     }
 
-    let path = String::from_utf8(output.stdout)
-        .context("Invalid UTF-8 in git output")?
-        .trim()
-        .to_string();
+    Ok(PathBuf::from(output.stdout.trim()))
+}
 
-    Ok(PathBuf::from(path))
+/// Resolve the main repository root of a linked worktree from its `.git` gitdir pointer.
+/// Returns `None` if `worktree_root` is not a linked worktree.
+pub fn main_repo_root(worktree_root: &Path) -> Option<PathBuf> {
+    // A linked worktree has a `.git` file containing "gitdir: <repo>/.git/worktrees/<name>"
+    let dot_git = std::fs::read_to_string(worktree_root.join(".git")).ok()?;
+    let gitdir = PathBuf::from(dot_git.trim().strip_prefix("gitdir:")?.trim());
+    let gitdir = worktree_root.join(gitdir);
+
+    // `commondir` points from the worktree gitdir to the shared `.git` directory
+    let common_dir = match std::fs::read_to_string(gitdir.join("commondir")) {
+        Ok(commondir) => gitdir.join(commondir.trim()),
+        Err(_) => gitdir.parent()?.parent()?.to_path_buf(),
+    };
+
+    common_dir.canonicalize().ok()?.parent().map(Path::to_path_buf)
 }
 
 /// Create a new worktree with a new branch
 pub fn worktree_add_new_branch(path: &Path, branch: &str) -> Result<()> {
-    let output = Command::new("git")
-        .args([
-            "worktree",
-            "add",
-            "-b",
-            branch,
-            path.to_string_lossy().as_ref(),
-        ])
-        .output()
-        .context("Failed to execute git worktree add")?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        bail!("Failed to create worktree: {}", stderr.trim());
-    }
-
+    git_checked(
+        &["worktree", "add", "-b", branch, &path.to_string_lossy()],
+        None,
+        "Failed to create worktree",
+    )?;
     Ok(())
 }
 
 /// Create a worktree at a specific commit/ref
 pub fn worktree_add_at_ref(path: &Path, git_ref: &str) -> Result<()> {
-    let output = Command::new("git")
-        .args(["worktree", "add", path.to_string_lossy().as_ref(), git_ref])
-        .output()
-        .context("Failed to execute git worktree add")?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        bail!("Failed to create worktree: {}", stderr.trim());
-    }
-
+    git_checked(
+        &["worktree", "add", &path.to_string_lossy(), git_ref],
+        None,
+        "Failed to create worktree",
+    )?;
     Ok(())
 }
 
 /// Create a worktree at a specific ref with a named branch
 pub fn worktree_add_with_branch(path: &Path, branch: &str, git_ref: &str) -> Result<()> {
-    let output = Command::new("git")
-        .args([
+    git_checked(
+        &[
             "worktree",
             "add",
             "-b",
             branch,
-            path.to_string_lossy().as_ref(),
+            &path.to_string_lossy(),
             git_ref,
-        ])
-        .output()
-        .context("Failed to execute git worktree add")?;
+        ],
+        None,
+        "Failed to create worktree",
+    )?;
+    Ok(())
+}
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        bail!("Failed to create worktree: {}", stderr.trim());
+/// First git version supporting `git worktree add --orphan`
+const WORKTREE_ORPHAN_VERSION: (u32, u32) = (2, 42);
+
+/// Create a worktree on a new orphan branch with no history and no files
+pub fn worktree_add_orphan(path: &Path, branch: &str) -> Result<()> {
+    let supports_orphan = version()
+        .ok()
+        .and_then(|v| parse_git_version(&v))
+        .is_some_and(|v| v >= WORKTREE_ORPHAN_VERSION);
+    add_orphan_worktree(path, branch, supports_orphan)
+}
+
+/// Create an orphan worktree. Without `worktree add --orphan`, add a detached worktree,
+/// switch it to an orphan branch, and remove the checked-out files.
+fn add_orphan_worktree(path: &Path, branch: &str, supports_orphan: bool) -> Result<()> {
+    let path_str = path.to_string_lossy();
+
+    if supports_orphan {
+        git_checked(
+            &["worktree", "add", "--orphan", "-b", branch, &path_str],
+            None,
+            "Failed to create worktree",
+        )?;
+        return Ok(());
     }
 
+    git_checked(
+        &["worktree", "add", "--detach", &path_str],
+        None,
+        "Failed to create worktree",
+    )?;
+    git_checked(
+        &["checkout", "--orphan", branch],
+        Some(path),
+        "Failed to create orphan branch",
+    )?;
+    git_checked(
+        &["rm", "-rf", "--quiet", "--ignore-unmatch", "."],
+        Some(path),
+        "Failed to clear worktree",
+    )?;
     Ok(())
 }
 
 /// Set upstream tracking for a branch in a worktree
 pub fn set_upstream(worktree_path: &Path, branch: &str, upstream: &str) -> Result<()> {
-    let output = Command::new("git")
-        .args(["branch", "--set-upstream-to", upstream, branch])
-        .current_dir(worktree_path)
-        .output()
-        .context("Failed to set upstream")?;
+    git_checked(
+        &["branch", "--set-upstream-to", upstream, branch],
+        Some(worktree_path),
+        "Failed to set upstream",
+    )?;
+    Ok(())
+}
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        bail!("Failed to set upstream: {}", stderr.trim());
-    }
+/// Push a branch to origin and set it as the branch's upstream
+pub fn push_set_upstream(branch: &str, repo_path: &Path) -> Result<()> {
+    git_checked(
+        &["push", "--set-upstream", "origin", branch],
+        Some(repo_path),
+        &format!("Failed to push {} to origin", branch),
+    )?;
+    Ok(())
+}
 
+/// Repair the links between a repository and its worktrees after either was moved
+pub fn worktree_repair(worktree_paths: &[&Path], repo_path: &Path) -> Result<()> {
+    let paths: Vec<String> = worktree_paths
+        .iter()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+    let mut args = vec!["worktree", "repair"];
+    args.extend(paths.iter().map(String::as_str));
+
+    git_checked(&args, Some(repo_path), "Failed to repair worktrees")?;
     Ok(())
 }
 
@@ -169,17 +456,7 @@ pub fn worktree_remove(path: &Path, force: bool, repo_path: &Path) -> Result<()>
     }
     args.push(&path_str);
 
-    let output = Command::new("git")
-        .args(&args)
-        .current_dir(repo_path)
-        .output()
-        .context("Failed to execute git worktree remove")?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        bail!("Failed to remove worktree: {}", stderr.trim());
-    }
-
+    git_checked(&args, Some(repo_path), "Failed to remove worktree")?;
     Ok(())
 }
 
@@ -187,19 +464,14 @@ pub fn worktree_remove(path: &Path, force: bool, repo_path: &Path) -> Result<()>
 pub fn branch_delete(branch: &str, force: bool, repo_path: &Path) -> Result<()> {
     let flag = if force { "-D" } else { "-d" };
 
-    let output = Command::new("git")
-        .args(["branch", flag, branch])
-        .current_dir(repo_path)
-        .output()
-        .context("Failed to execute git branch delete")?;
+    let output = git(&["branch", flag, branch], Some(repo_path))?;
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.success {
         // Don't fail if branch doesn't exist or can't be deleted
         eprintln!(
             "Warning: Could not delete branch {}: {}",
             branch,
-            stderr.trim()
+            output.stderr.trim()
         );
     }
 
@@ -207,119 +479,464 @@ pub fn branch_delete(branch: &str, force: bool, repo_path: &Path) -> Result<()>
 }
 
 /// Check if there are uncommitted changes
-pub fn has_uncommitted_changes() -> Result<bool> {
-    let output = Command::new("git")
-        .args(["status", "--porcelain"])
-        .output()
-        .context("Failed to execute git status")?;
+pub fn has_uncommitted_changes(repo_path: &Path) -> Result<bool> {
+    let output = git(&["status", "--porcelain"], Some(repo_path))?;
 
-    if !output.status.success() {
+    if !output.success {
         bail!("Failed to check git status");
     }
 
     Ok(!output.stdout.is_empty())
 }
 
-/// Get PR branch name using gh CLI
-pub fn get_pr_branch(pr_number: u32) -> Result<String> {
+/// Describe the rebase or merge in progress in a worktree, if any
+pub fn in_progress_operation(repo_path: &Path) -> Result<Option<&'static str>> {
+    let git_dir = git_checked(
+        &["rev-parse", "--absolute-git-dir"],
+        Some(repo_path),
+        "Failed to locate git directory",
+    )?;
+    let git_dir = Path::new(&git_dir);
+
+    if git_dir.join("rebase-merge").exists() || git_dir.join("rebase-apply").exists() {
+        return Ok(Some("rebase"));
+    }
+    if git_dir.join("MERGE_HEAD").exists() {
+        return Ok(Some("merge"));
+    }
+    Ok(None)
+}
+
+/// Stage all changes and commit them
+pub fn commit_all(message: &str, repo_path: &Path) -> Result<()> {
+    git_checked(&["add", "-A"], Some(repo_path), "Failed to stage changes")?;
+    git_checked(
+        &["commit", "-m", message],
+        Some(repo_path),
+        "Failed to commit changes",
+    )?;
+    Ok(())
+}
+
+/// Stash all changes (including untracked files) in a repository
+pub fn stash_push(message: &str, repo_path: &Path) -> Result<()> {
+    git_checked(
+        &["stash", "push", "--include-untracked", "-m", message],
+        Some(repo_path),
+        "Failed to stash changes",
+    )?;
+    Ok(())
+}
+
+/// Apply the latest stash entry without removing it
+pub fn stash_apply(repo_path: &Path) -> Result<()> {
+    git_checked(
+        &["stash", "apply", "stash@{0}"],
+        Some(repo_path),
+        "Failed to apply stash",
+    )?;
+    Ok(())
+}
+
+/// Apply the latest stash entry and remove it
+pub fn stash_pop(repo_path: &Path) -> Result<()> {
+    git_checked(&["stash", "pop"], Some(repo_path), "Failed to pop stash")?;
+    Ok(())
+}
+
+/// Drop the latest stash entry
+pub fn stash_drop(repo_path: &Path) -> Result<()> {
+    git_checked(
+        &["stash", "drop", "stash@{0}"],
+        Some(repo_path),
+        "Failed to drop stash",
+    )?;
+    Ok(())
+}
+
+/// Head branch of a pull request as reported by gh CLI
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PrHead {
+    #[serde(rename = "headRefName")]
+    pub branch: String,
+    /// Whether the head branch lives in a fork rather than the base repository
+    #[serde(rename = "isCrossRepository", default)]
+    pub is_cross_repository: bool,
+    #[serde(rename = "headRepositoryOwner")]
+    pub owner: Option<PrHeadOwner>,
+}
+
+/// Owner of a PR's head repository
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PrHeadOwner {
+    pub login: String,
+}
+
+impl PrHead {
+    /// Local branch name: the head branch, prefixed with `<owner>/` for forks
+    /// (like `gh pr checkout`) so it cannot clash with a branch of the base repository
+    pub fn local_branch(&self) -> String {
+        match &self.owner {
+            Some(owner) if self.is_cross_repository => format!("{}/{}", owner.login, self.branch),
+            _ => self.branch.clone(),
+        }
+    }
+}
+
+/// Environment variable with a token for the GitHub REST API, used when gh is not installed
+pub const GITHUB_TOKEN_ENV: &str = "GITHUB_TOKEN";
+
+/// Get the PR head branch (and whether it is in a fork) using gh CLI,
+/// falling back to the GitHub REST API when gh is missing and `GITHUB_TOKEN` is set
+pub fn get_pr_head(pr_number: u32) -> Result<PrHead> {
     // First check if gh is available
     if !is_gh_available() {
-        bail!("gh CLI is not installed. Please install it from https://cli.github.com/");
+        return match std::env::var(GITHUB_TOKEN_ENV) {
+            Ok(token) if !token.trim().is_empty() => get_pr_head_from_api(pr_number, token.trim()),
+            _ => Err(GjError::GhNotInstalled.into()),
+        };
     }
 
-    let output = Command::new("gh")
-        .args([
+    let output = gh(
+        &[
             "pr",
             "view",
             &pr_number.to_string(),
             "--json",
-            "headRefName",
-            "-q",
-            ".headRefName",
-        ])
-        .output()
-        .context("Failed to execute gh pr view")?;
+            "headRefName,isCrossRepository,headRepositoryOwner",
+        ],
+        None,
+    )
+    .context("Failed to execute gh pr view")?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        bail!("Failed to get PR #{} info: {}", pr_number, stderr.trim());
+        match classify_gh_error(&stderr) {
+            Some(GhError::NotAuthenticated) => return Err(GjError::GhNotAuthenticated.into()),
+            Some(GhError::PrNotFound) => bail!(
+                "PR #{} does not exist in this repository. Check the PR number.",
+                pr_number
+            ),
+            None => bail!("Failed to get PR #{} info: {}", pr_number, stderr.trim()),
+        }
     }
 
-    let branch = String::from_utf8(output.stdout)
-        .context("Invalid UTF-8 in gh output")?
-        .trim()
-        .to_string();
+    let head = parse_pr_head(&output.stdout)?;
 
-    if branch.is_empty() {
+    if head.branch.is_empty() {
         bail!("PR #{} not found or has no branch", pr_number);
     }
 
-    Ok(branch)
+    Ok(head)
 }
 
-/// Fetch a branch from origin
-pub fn fetch_branch(branch: &str) -> Result<()> {
-    let output = Command::new("git")
-        .args(["fetch", "origin", branch])
-        .output()
-        .context("Failed to fetch branch")?;
+/// Parse the JSON output of `gh pr view --json headRefName,...`
+fn parse_pr_head(json: &[u8]) -> Result<PrHead> {
+    serde_json::from_slice(json).context("Failed to parse gh pr view output")
+}
+
+/// Pull request as returned by `GET /repos/{owner}/{repo}/pulls/{number}`
+#[derive(Deserialize)]
+struct ApiPull {
+    head: ApiPullRef,
+    base: ApiPullRef,
+}
+
+/// Head or base of a pull request in the REST API
+#[derive(Deserialize)]
+struct ApiPullRef {
+    #[serde(rename = "ref")]
+    ref_name: String,
+    /// Null when the fork has been deleted
+    repo: Option<ApiRepo>,
+}
+
+/// Repository of a pull request ref in the REST API
+#[derive(Deserialize)]
+struct ApiRepo {
+    full_name: String,
+    owner: PrHeadOwner,
+}
+
+/// Get the PR head from the GitHub REST API with curl, authenticating with `token`
+fn get_pr_head_from_api(pr_number: u32, token: &str) -> Result<PrHead> {
+    let repo = get_github_repo_info()?;
+    let url = api_pull_url(&repo, pr_number);
+
+    // The header is passed on stdin so the token never shows up in the process list
+    let mut command = Command::new("curl");
+    command
+        .args(["--silent", "--show-error", "--location"])
+        .args(["--header", "@-"])
+        .args(["--header", "Accept: application/vnd.github+json"])
+        .args(["--write-out", "\n%{http_code}"])
+        .arg(&url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let mut child = traced(&mut command)
+        .spawn()
+        .context("gh is not installed and curl could not be run for the GitHub API")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        writeln!(stdin, "Authorization: Bearer {}", token)
+            .context("Failed to pass GITHUB_TOKEN to curl")?;
+    }
+    let output = child.wait_with_output().context("Failed to run curl")?;
+
+    if !output.status.success() {
+        bail!(
+            "Failed to get PR #{} from the GitHub API: {}",
+            pr_number,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (body, status) = stdout.rsplit_once('\n').unwrap_or(("", &stdout));
+    match status.trim() {
+        "200" => parse_api_pull(body.as_bytes()),
+        "401" | "403" => bail!(
+            "The GitHub API rejected {} (HTTP {}). Check that the token can read this repository.",
+            GITHUB_TOKEN_ENV,
+            status.trim()
+        ),
+        "404" => bail!(
+            "PR #{} does not exist in this repository. Check the PR number.",
+            pr_number
+        ),
+        status => bail!(
+            "Failed to get PR #{} from the GitHub API (HTTP {})",
+            pr_number,
+            status
+        ),
+    }
+}
+
+/// REST API URL of a pull request. github.com serves the API from `api.github.com`,
+/// GitHub Enterprise from `/api/v3` on its own host.
+fn api_pull_url(repo: &GitHubRepo, pr_number: u32) -> String {
+    let base = if repo.host == GITHUB_HOST {
+        format!("https://api.{}", GITHUB_HOST)
+    } else {
+        format!("https://{}/api/v3", repo.host)
+    };
+    format!(
+        "{}/repos/{}/{}/pulls/{}",
+        base, repo.owner, repo.repo, pr_number
+    )
+}
+
+/// Parse a REST API pull request into the same shape `gh pr view` gives
+fn parse_api_pull(json: &[u8]) -> Result<PrHead> {
+    let pull: ApiPull =
+        serde_json::from_slice(json).context("Failed to parse GitHub API response")?;
+
+    let base_name = pull.base.repo.map(|repo| repo.full_name);
+    let is_cross_repository = match &pull.head.repo {
+        Some(head) => Some(&head.full_name) != base_name.as_ref(),
+        None => true,
+    };
+
+    Ok(PrHead {
+        branch: pull.head.ref_name,
+        is_cross_repository,
+        owner: pull.head.repo.map(|repo| repo.owner),
+    })
+}
+
+/// Number of the PR for the current branch, if gh finds one
+pub fn current_branch_pr() -> Option<u32> {
+    let output = gh(&["pr", "view", "--json", "number", "-q", ".number"], None).ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+/// Post a comment on a PR using gh CLI
+pub fn comment_on_pr(pr_number: u32, body: &str, repo_path: &Path) -> Result<()> {
+    let output = gh(
+        &["pr", "comment", &pr_number.to_string(), "--body", body],
+        Some(repo_path),
+    )
+    .context("Failed to execute gh pr comment")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if classify_gh_error(&stderr) == Some(GhError::NotAuthenticated) {
+            return Err(GjError::GhNotAuthenticated.into());
+        }
+        bail!("Failed to comment on PR #{}: {}", pr_number, stderr.trim());
+    }
+
+    Ok(())
+}
+
+/// Get the login of the GitHub user authenticated in gh CLI
+pub fn get_gh_user() -> Result<String> {
+    let output =
+        gh(&["api", "user", "--jq", ".login"], None).context("Failed to execute gh api user")?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        bail!("Failed to fetch branch {}: {}", branch, stderr.trim());
+        bail!("Failed to get GitHub user: {}", stderr.trim());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// List open PRs using gh CLI
+pub fn list_open_prs() -> Result<Vec<PrSummary>> {
+    if !is_gh_available() {
+        return Err(GjError::GhNotInstalled.into());
+    }
+
+    let output = gh(&["pr", "list", "--json", "number,title,headRefName"], None)
+        .context("Failed to execute gh pr list")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if classify_gh_error(&stderr) == Some(GhError::NotAuthenticated) {
+            return Err(GjError::GhNotAuthenticated.into());
+        }
+        bail!("Failed to list PRs: {}", stderr.trim());
+    }
+
+    parse_pr_list(&output.stdout)
+}
+
+/// Known gh CLI failure categories
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GhError {
+    /// gh is installed but not logged in
+    NotAuthenticated,
+    /// The requested PR number does not exist
+    PrNotFound,
+}
+
+/// Classify gh CLI stderr into a known failure category
+fn classify_gh_error(stderr: &str) -> Option<GhError> {
+    let stderr = stderr.to_lowercase();
+
+    if stderr.contains("gh auth login")
+        || stderr.contains("not logged in")
+        || stderr.contains("authentication required")
+        || stderr.contains("bad credentials")
+        || stderr.contains("gh_token")
+    {
+        return Some(GhError::NotAuthenticated);
+    }
+
+    if stderr.contains("could not resolve to a pullrequest") {
+        return Some(GhError::PrNotFound);
     }
 
+    None
+}
+
+/// Parse the JSON output of `gh pr list`
+fn parse_pr_list(json: &[u8]) -> Result<Vec<PrSummary>> {
+    serde_json::from_slice(json).context("Failed to parse gh pr list output")
+}
+
+/// Fetch a branch from origin
+pub fn fetch_branch(branch: &str) -> Result<()> {
+    git_checked(
+        &["fetch", "origin", branch],
+        None,
+        &format!("Failed to fetch branch {}", branch),
+    )?;
+    Ok(())
+}
+
+/// Fetch a PR's head commit from origin into `refs/remotes/origin/pull/<n>/head`.
+/// Works for PRs from forks, whose head branch does not exist on origin.
+pub fn fetch_pull_head(pr_number: u32) -> Result<()> {
+    let refspec = format!(
+        "+refs/pull/{0}/head:refs/remotes/origin/pull/{0}/head",
+        pr_number
+    );
+    git_checked(
+        &["fetch", "origin", &refspec],
+        None,
+        &format!("Failed to fetch PR #{}", pr_number),
+    )?;
     Ok(())
 }
 
+/// Get the installed git version string (e.g., "git version 2.43.0")
+pub fn version() -> Result<String> {
+    // Spawned directly: `git_command` runs the version check, which calls this
+    let mut command = Command::new(git_path());
+    command.arg("--version");
+    let started = Instant::now();
+    let output = traced(&mut command).output();
+    crate::debug_log::process(&command, started, output_code(&output));
+    let output = output.context("Failed to execute git. Is git installed?")?;
+
+    if !output.status.success() {
+        bail!("git --version failed");
+    }
+
+    let version = String::from_utf8(output.stdout)
+        .context("Invalid UTF-8 in git output")?
+        .trim()
+        .to_string();
+
+    Ok(version)
+}
+
 /// Check if gh CLI is available
 pub fn is_gh_available() -> bool {
-    Command::new("gh")
-        .arg("--version")
-        .output()
+    gh(&["--version"], None)
         .map(|o| o.status.success())
         .unwrap_or(false)
 }
 
+/// Get the installed gh version (first line of `gh --version`), if gh is available
+pub fn gh_version() -> Option<String> {
+    let output = gh(&["--version"], None).ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .map(|l| l.trim().to_string())
+}
+
 /// Get the default branch name from origin
 pub fn get_default_branch(repo_path: &Path) -> Result<String> {
     // Try to get from origin/HEAD
-    let output = Command::new("git")
-        .args(["symbolic-ref", "refs/remotes/origin/HEAD"])
-        .current_dir(repo_path)
-        .output()
-        .context("Failed to get default branch")?;
-
-    if output.status.success() {
-        let ref_name = String::from_utf8(output.stdout)
-            .context("Invalid UTF-8 in git output")?
-            .trim()
-            .to_string();
+    let output = git(
+        &["symbolic-ref", "refs/remotes/origin/HEAD"],
+        Some(repo_path),
+    )?;
+
+    if output.success {
         // refs/remotes/origin/main -> main
-        if let Some(branch) = ref_name.strip_prefix("refs/remotes/origin/") {
+        if let Some(branch) = output.stdout.trim().strip_prefix("refs/remotes/origin/") {
             return Ok(branch.to_string());
         }
     }
 
     // Fallback: check if main exists
-    let output = Command::new("git")
-        .args(["rev-parse", "--verify", "refs/heads/main"])
-        .current_dir(repo_path)
-        .output()
-        .context("Failed to check main branch")?;
+    let output = git(
+        &["rev-parse", "--verify", "refs/heads/main"],
+        Some(repo_path),
+    )?;
 
-    if output.status.success() {
+    if output.success {
         return Ok("main".to_string());
     }
 
     // Fallback: check if master exists
-    let output = Command::new("git")
-        .args(["rev-parse", "--verify", "refs/heads/master"])
-        .current_dir(repo_path)
-        .output()
-        .context("Failed to check master branch")?;
+    let output = git(
+        &["rev-parse", "--verify", "refs/heads/master"],
+        Some(repo_path),
+    )?;
 
-    if output.status.success() {
+    if output.success {
         return Ok("master".to_string());
     }
 
@@ -329,108 +946,385 @@ pub fn get_default_branch(repo_path: &Path) -> Result<String> {
 /// Checkout a branch
 #[allow(dead_code)]
 pub fn checkout_branch(branch: &str, repo_path: &Path) -> Result<()> {
-    let output = Command::new("git")
-        .args(["checkout", branch])
-        .current_dir(repo_path)
-        .output()
-        .context("Failed to checkout branch")?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        bail!("Failed to checkout branch {}: {}", branch, stderr.trim());
-    }
-
+    git_checked(
+        &["checkout", branch],
+        Some(repo_path),
+        &format!("Failed to checkout branch {}", branch),
+    )?;
     Ok(())
 }
 
 /// Merge a branch into the current branch
 pub fn merge_branch(branch: &str, repo_path: &Path) -> Result<()> {
-    let output = Command::new("git")
-        .args(["merge", branch, "--no-edit"])
-        .current_dir(repo_path)
-        .output()
-        .context("Failed to merge branch")?;
+    git_checked(
+        &["merge", branch, "--no-edit"],
+        Some(repo_path),
+        &format!("Failed to merge branch {}", branch),
+    )?;
+    Ok(())
+}
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        bail!("Failed to merge branch {}: {}", branch, stderr.trim());
-    }
+/// Merge a branch into the current branch with a custom commit message.
+/// The branch may share no history with the current one (e.g. an orphan scaffold branch).
+pub fn merge_branch_with_message(branch: &str, message: &str, repo_path: &Path) -> Result<()> {
+    git_checked(
+        &[
+            "merge",
+            "--no-edit",
+            "--allow-unrelated-histories",
+            "-m",
+            message,
+            branch,
+        ],
+        Some(repo_path),
+        &format!("Failed to merge branch {}", branch),
+    )?;
+    Ok(())
+}
 
+/// Fast-forward the current branch to a branch, failing if that is not possible
+pub fn merge_branch_ff_only(branch: &str, repo_path: &Path) -> Result<()> {
+    git_checked(
+        &["merge", "--ff-only", branch],
+        Some(repo_path),
+        &format!("Failed to fast-forward to branch {}", branch),
+    )?;
     Ok(())
 }
 
 /// Abort an in-progress merge
 pub fn merge_abort(repo_path: &Path) -> Result<()> {
-    let output = Command::new("git")
-        .args(["merge", "--abort"])
-        .current_dir(repo_path)
-        .output()
-        .context("Failed to abort merge")?;
+    git_checked(
+        &["merge", "--abort"],
+        Some(repo_path),
+        "Failed to abort merge",
+    )?;
+    Ok(())
+}
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        bail!("Failed to abort merge: {}", stderr.trim());
+/// Rebase the current branch onto another branch
+pub fn rebase_onto(onto: &str, repo_path: &Path) -> Result<()> {
+    git_checked(
+        &["rebase", onto],
+        Some(repo_path),
+        &format!("Failed to rebase onto {}", onto),
+    )?;
+    Ok(())
+}
+
+/// Abort an in-progress rebase
+pub fn rebase_abort(repo_path: &Path) -> Result<()> {
+    git_checked(
+        &["rebase", "--abort"],
+        Some(repo_path),
+        "Failed to abort rebase",
+    )?;
+    Ok(())
+}
+
+/// List tracked and untracked files in a worktree, excluding ignored files
+pub fn list_files(repo_path: &Path) -> Result<Vec<String>> {
+    let stdout = git_checked(
+        &["ls-files", "-z", "--cached", "--others", "--exclude-standard"],
+        Some(repo_path),
+        "Failed to list files",
+    )?;
+
+    Ok(stdout
+        .split('\0')
+        .filter(|f| !f.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+/// List untracked paths matched by ignore rules; an ignored directory is listed once, as `dir/`
+pub fn list_ignored(repo_path: &Path) -> Result<Vec<String>> {
+    let stdout = git_checked(
+        &[
+            "ls-files",
+            "-z",
+            "--others",
+            "--ignored",
+            "--exclude-standard",
+            "--directory",
+        ],
+        Some(repo_path),
+        "Failed to list ignored files",
+    )?;
+
+    Ok(stdout
+        .split('\0')
+        .filter(|f| !f.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+/// Resolve a revision to its full commit SHA
+pub fn rev_parse(rev: &str, repo_path: &Path) -> Result<String> {
+    git_checked(
+        &["rev-parse", rev],
+        Some(repo_path),
+        &format!("Failed to resolve {}", rev),
+    )
+}
+
+/// List local branches whose tips are reachable from a target branch
+pub fn merged_branches(target: &str, repo_path: &Path) -> Result<Vec<String>> {
+    let stdout = git_checked(
+        &["branch", "--merged", target, "--format=%(refname:short)"],
+        Some(repo_path),
+        "Failed to list merged branches",
+    )?;
+    Ok(stdout.lines().map(|l| l.to_string()).collect())
+}
+
+/// Upstream configured for a branch
+#[derive(Debug, PartialEq)]
+pub enum Upstream {
+    /// No upstream is configured
+    None,
+    /// The upstream, e.g. `origin/main`, resolves
+    Tracking(String),
+    /// The upstream is configured but doesn't resolve (e.g. the remote branch was deleted)
+    Gone(String),
+}
+
+/// Get the upstream of a branch and whether it resolves
+pub fn upstream(branch: &str, repo_path: &Path) -> Result<Upstream> {
+    let rev = format!("{}@{{upstream}}", branch);
+    let output = git(&["rev-parse", "--abbrev-ref", &rev], Some(repo_path))?;
+    if output.success {
+        return Ok(Upstream::Tracking(output.stdout.trim().to_string()));
+    }
+
+    // rev-parse fails both without an upstream and with a missing one; the config tells them apart
+    let reference = format!("refs/heads/{}", branch);
+    let configured = git_checked(
+        &["for-each-ref", "--format=%(upstream:short)", &reference],
+        Some(repo_path),
+        "Failed to read upstream",
+    )?;
+    Ok(match configured.trim() {
+        "" => Upstream::None,
+        name => Upstream::Gone(name.to_string()),
+    })
+}
+
+/// Count commits ahead of and behind the upstream branch, as `(ahead, behind)`.
+/// Returns `None` if the branch has no upstream.
+pub fn ahead_behind(repo_path: &Path) -> Result<Option<(usize, usize)>> {
+    ahead_behind_of("@{upstream}", repo_path)
+}
+
+/// Count commits HEAD is ahead of and behind `base`; None if `base` doesn't resolve
+pub fn ahead_behind_of(base: &str, repo_path: &Path) -> Result<Option<(usize, usize)>> {
+    let range = format!("{}...HEAD", base);
+    let output = git(
+        &["rev-list", "--left-right", "--count", &range],
+        Some(repo_path),
+    )?;
+
+    if !output.success {
+        return Ok(None);
+    }
+
+    Ok(parse_left_right_count(&output.stdout).map(|(behind, ahead)| (ahead, behind)))
+}
+
+/// Parse `git rev-list --left-right --count` output ("<left>\t<right>")
+fn parse_left_right_count(stdout: &str) -> Option<(usize, usize)> {
+    let mut counts = stdout.split_whitespace();
+    let left = counts.next()?.parse().ok()?;
+    let right = counts.next()?.parse().ok()?;
+    Some((left, right))
+}
+
+/// Commits a ref has pointed at, newest first, from its reflog; empty if it has none
+pub fn ref_history(git_ref: &str, repo_path: &Path) -> Result<Vec<String>> {
+    let output = git(
+        &["reflog", "show", "--format=%H", git_ref, "--"],
+        Some(repo_path),
+    )?;
+
+    if !output.success {
+        return Ok(Vec::new());
+    }
+
+    Ok(output.stdout.lines().map(|l| l.to_string()).collect())
+}
+
+/// Count commits reachable from `rev` but from none of `known`
+pub fn count_commits_not_on(rev: &str, known: &[String], repo_path: &Path) -> Result<usize> {
+    let mut args = vec!["rev-list", "--count", rev, "--not"];
+    args.extend(known.iter().map(|k| k.as_str()));
+    let stdout = git_checked(&args, Some(repo_path), "Failed to count commits")?;
+    stdout
+        .parse()
+        .with_context(|| format!("Unexpected rev-list output: {}", stdout))
+}
+
+/// Check that a name is a valid branch name (`git check-ref-format --branch`)
+pub fn check_branch_name(branch: &str) -> Result<()> {
+    let output = git(&["check-ref-format", "--branch", branch], None)?;
+
+    if !output.success {
+        bail!("Invalid branch name: '{}'", branch);
     }
 
     Ok(())
 }
 
+/// Check whether a ref resolves to a commit
+pub fn ref_exists(git_ref: &str, repo_path: &Path) -> Result<bool> {
+    let rev = format!("{}^{{commit}}", git_ref);
+    let output = git(&["rev-parse", "--verify", "--quiet", &rev], Some(repo_path))?;
+    Ok(output.success)
+}
+
+/// Check whether a local branch exists
+pub fn local_branch_exists(branch: &str, repo_path: &Path) -> Result<bool> {
+    let git_ref = format!("refs/heads/{}", branch);
+    let output = git(
+        &["show-ref", "--verify", "--quiet", &git_ref],
+        Some(repo_path),
+    )?;
+    Ok(output.success)
+}
+
 /// Find the worktree path that has a specific branch checked out
 pub fn find_worktree_for_branch(branch: &str, repo_path: &Path) -> Result<Option<PathBuf>> {
-    let output = Command::new("git")
-        .args(["worktree", "list", "--porcelain"])
-        .current_dir(repo_path)
-        .output()
-        .context("Failed to list worktrees")?;
+    Ok(list_worktrees(repo_path)?
+        .into_iter()
+        .find(|w| w.branch.as_deref() == Some(branch))
+        .map(|w| w.path))
+}
 
-    if !output.status.success() {
+/// A worktree reported by `git worktree list --porcelain`
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorktreeEntry {
+    pub path: PathBuf,
+    /// Checked-out branch (None when detached or bare)
+    pub branch: Option<String>,
+}
+
+/// List the worktrees of a repository, the main worktree first
+pub fn list_worktrees(repo_path: &Path) -> Result<Vec<WorktreeEntry>> {
+    let output = git(&["worktree", "list", "--porcelain"], Some(repo_path))?;
+
+    if !output.success {
         bail!("Failed to list worktrees");
     }
 
-    let output_str = String::from_utf8(output.stdout).context("Invalid UTF-8 in git output")?;
+    Ok(parse_worktree_list(&output.stdout))
+}
 
-    let mut current_worktree: Option<PathBuf> = None;
+/// Parse `git worktree list --porcelain` output
+fn parse_worktree_list(stdout: &str) -> Vec<WorktreeEntry> {
+    let mut worktrees: Vec<WorktreeEntry> = Vec::new();
 
-    for line in output_str.lines() {
+    for line in stdout.lines() {
         if let Some(path) = line.strip_prefix("worktree ") {
-            current_worktree = Some(PathBuf::from(path));
+            worktrees.push(WorktreeEntry {
+                path: PathBuf::from(path),
+                branch: None,
+            });
         } else if let Some(branch_name) = line.strip_prefix("branch refs/heads/") {
-            if branch_name == branch {
-                return Ok(current_worktree);
+            if let Some(worktree) = worktrees.last_mut() {
+                worktree.branch = Some(branch_name.to_string());
             }
         }
     }
 
-    Ok(None)
+    worktrees
 }
 
 /// Get the current branch name
 #[allow(dead_code)]
 pub fn current_branch() -> Result<Option<String>> {
-    let output = Command::new("git")
-        .args(["rev-parse", "--abbrev-ref", "HEAD"])
-        .output()
-        .context("Failed to get current branch")?;
+    let output = git(&["rev-parse", "--abbrev-ref", "HEAD"], None)?;
 
-    if !output.status.success() {
+    if !output.success {
         return Ok(None);
     }
 
-    let branch = String::from_utf8(output.stdout)
-        .context("Invalid UTF-8 in git output")?
-        .trim()
-        .to_string();
+    let branch = output.stdout.trim();
 
     if branch == "HEAD" {
         // Detached HEAD state
         return Ok(None);
     }
 
-    Ok(Some(branch))
+    Ok(Some(branch.to_string()))
+}
+
+/// Mock git runner for unit tests
+#[cfg(test)]
+pub mod testing {
+    use super::*;
+
+    /// Returns canned output for exact argument lists and records every call
+    #[derive(Default)]
+    pub struct MockGitRunner {
+        responses: Vec<(Vec<String>, GitOutput)>,
+        calls: RefCell<Vec<Vec<String>>>,
+    }
+
+    impl MockGitRunner {
+        /// Respond to `args` with a successful result printing `stdout`
+        pub fn ok(mut self, args: &[&str], stdout: &str) -> Self {
+            self.responses.push((
+                args.iter().map(|a| a.to_string()).collect(),
+                GitOutput {
+                    success: true,
+                    stdout: stdout.to_string(),
+                    stderr: String::new(),
+                },
+            ));
+            self
+        }
+
+        /// Respond to `args` with a failed result printing `stderr`
+        pub fn fail(mut self, args: &[&str], stderr: &str) -> Self {
+            self.responses.push((
+                args.iter().map(|a| a.to_string()).collect(),
+                GitOutput {
+                    success: false,
+                    stdout: String::new(),
+                    stderr: stderr.to_string(),
+                },
+            ));
+            self
+        }
+
+        /// Argument lists of every command run so far
+        pub fn calls(&self) -> Vec<Vec<String>> {
+            self.calls.borrow().clone()
+        }
+    }
+
+    impl GitRunner for MockGitRunner {
+        fn run(&self, args: &[&str], _dir: Option<&Path>) -> Result<GitOutput> {
+            let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+            self.calls.borrow_mut().push(args.clone());
+
+            // Unexpected commands fail like an unknown git subcommand would
+            Ok(self
+                .responses
+                .iter()
+                .find(|(expected, _)| *expected == args)
+                .map(|(_, output)| output.clone())
+                .unwrap_or_else(|| GitOutput {
+                    success: false,
+                    stdout: String::new(),
+                    stderr: format!("unexpected git command: {}", args.join(" ")),
+                }))
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::testing::MockGitRunner;
     use super::*;
     use std::fs;
     use std::sync::Mutex;
@@ -507,21 +1401,6 @@ mod tests {
         temp_dir
     }
 
-    /// Helper to run git status in a specific directory
-    fn has_uncommitted_changes_in(repo_path: &Path) -> Result<bool> {
-        let output = Command::new("git")
-            .args(["status", "--porcelain"])
-            .current_dir(repo_path)
-            .output()
-            .context("Failed to execute git status")?;
-
-        if !output.status.success() {
-            bail!("Failed to check git status");
-        }
-
-        Ok(!output.stdout.is_empty())
-    }
-
     /// Helper to get current branch in a specific directory
     fn current_branch_in(repo_path: &Path) -> Result<Option<String>> {
         let output = Command::new("git")
@@ -608,8 +1487,11 @@ mod tests {
         let temp_dir = create_temp_git_repo();
         let repo_path = temp_dir.path();
 
-        let has_changes = has_uncommitted_changes_in(repo_path).expect("Should check status");
-        assert!(!has_changes, "Clean repo should have no uncommitted changes");
+        let has_changes = has_uncommitted_changes(repo_path).expect("Should check status");
+        assert!(
+            !has_changes,
+            "Clean repo should have no uncommitted changes"
+        );
     }
 
     #[test]
@@ -620,7 +1502,7 @@ mod tests {
         // Modify a file
         fs::write(repo_path.join("README.md"), "# Modified").expect("Failed to modify file");
 
-        let has_changes = has_uncommitted_changes_in(repo_path).expect("Should check status");
+        let has_changes = has_uncommitted_changes(repo_path).expect("Should check status");
         assert!(has_changes, "Repo with unstaged changes should be dirty");
     }
 
@@ -637,7 +1519,7 @@ mod tests {
             .output()
             .expect("Failed to stage file");
 
-        let has_changes = has_uncommitted_changes_in(repo_path).expect("Should check status");
+        let has_changes = has_uncommitted_changes(repo_path).expect("Should check status");
         assert!(has_changes, "Repo with staged changes should be dirty");
     }
 
@@ -718,6 +1600,57 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_worktree_under_base_dir_with_spaces() {
+        let _guard = CWD_MUTEX.lock().unwrap();
+        let temp_dir = create_temp_git_repo();
+        let repo_path = temp_dir.path();
+        std::env::set_current_dir(repo_path).expect("Failed to change directory");
+
+        let base_dir = TempDir::new().unwrap();
+        let worktree_path = base_dir
+            .path()
+            .join("my worktrees")
+            .join("owner")
+            .join("repo")
+            .join("gj/20260205_it's a test");
+        worktree_add_new_branch(&worktree_path, "gj/20260205_spaces")
+            .expect("Should create worktree");
+
+        assert!(worktree_path.join(".git").exists());
+        assert!(!has_uncommitted_changes(&worktree_path).unwrap());
+        assert_eq!(
+            main_repo_root(&worktree_path).map(|p| p.canonicalize().unwrap()),
+            Some(repo_path.canonicalize().unwrap())
+        );
+
+        worktree_remove(&worktree_path, false, repo_path).expect("Should remove worktree");
+        assert!(!worktree_path.exists());
+    }
+
+    #[test]
+    fn test_main_repo_root() {
+        let temp_dir = create_temp_git_repo();
+        let repo_path = temp_dir.path();
+
+        let worktree_dir = TempDir::new().unwrap();
+        let worktree_path = worktree_dir.path().join("linked");
+        let output = Command::new("git")
+            .args(["worktree", "add", "-b", "linked"])
+            .arg(&worktree_path)
+            .current_dir(repo_path)
+            .output()
+            .expect("Failed to add worktree");
+        assert!(output.status.success());
+
+        assert_eq!(
+            main_repo_root(&worktree_path),
+            Some(repo_path.canonicalize().unwrap())
+        );
+        // The main repository itself is not a linked worktree
+        assert_eq!(main_repo_root(repo_path), None);
+    }
+
     #[test]
     fn test_branch_delete() {
         let temp_dir = create_temp_git_repo();
@@ -758,12 +1691,516 @@ mod tests {
         assert!(output.stdout.is_empty(), "Branch should be deleted");
     }
 
+    #[test]
+    fn test_commit_all() {
+        let temp_dir = create_temp_git_repo();
+        let repo_path = temp_dir.path();
+
+        fs::write(repo_path.join("README.md"), "# Modified").expect("Failed to modify file");
+        fs::write(repo_path.join("new.txt"), "new content").expect("Failed to create file");
+
+        commit_all("Wrap up", repo_path).expect("Should commit changes");
+        assert!(!has_uncommitted_changes(repo_path).unwrap());
+
+        let output = Command::new("git")
+            .args(["log", "-1", "--format=%s"])
+            .current_dir(repo_path)
+            .output()
+            .expect("Failed to read log");
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "Wrap up");
+    }
+
+    #[test]
+    fn test_stash_carry_into_worktree() {
+        let temp_dir = create_temp_git_repo();
+        let repo_path = temp_dir.path();
+
+        // Make uncommitted changes (modified and untracked)
+        fs::write(repo_path.join("README.md"), "# Modified").expect("Failed to modify file");
+        fs::write(repo_path.join("new.txt"), "new content").expect("Failed to create file");
+
+        stash_push("carry test", repo_path).expect("Should stash changes");
+        assert!(!has_uncommitted_changes(repo_path).unwrap());
+
+        // Create a worktree and apply the stash there
+        let worktree_dir = TempDir::new().unwrap();
+        let worktree_path = worktree_dir.path().join("carry");
+        let output = Command::new("git")
+            .args(["worktree", "add", "-b", "carry-branch"])
+            .arg(&worktree_path)
+            .current_dir(repo_path)
+            .output()
+            .expect("Failed to add worktree");
+        assert!(output.status.success());
+
+        stash_apply(&worktree_path).expect("Should apply stash");
+        stash_drop(&worktree_path).expect("Should drop stash");
+
+        assert_eq!(
+            fs::read_to_string(worktree_path.join("README.md")).unwrap(),
+            "# Modified"
+        );
+        assert!(worktree_path.join("new.txt").exists());
+
+        worktree_remove(&worktree_path, true, repo_path).expect("Should remove worktree");
+    }
+
+    #[test]
+    fn test_find_worktree_for_branch_with_mock() {
+        let runner = Rc::new(MockGitRunner::default().ok(
+            &["worktree", "list", "--porcelain"],
+            "worktree /repo\nHEAD abc\nbranch refs/heads/main\n\n\
+             worktree /wt/feature\nHEAD def\nbranch refs/heads/feature\n",
+        ));
+
+        with_runner(runner.clone(), || {
+            assert_eq!(
+                find_worktree_for_branch("feature", Path::new("/repo")).unwrap(),
+                Some(PathBuf::from("/wt/feature"))
+            );
+            assert_eq!(
+                find_worktree_for_branch("missing", Path::new("/repo")).unwrap(),
+                None
+            );
+        });
+
+        assert_eq!(runner.calls().len(), 2);
+    }
+
+    #[test]
+    fn test_list_files_with_mock() {
+        let runner = Rc::new(MockGitRunner::default().ok(
+            &["ls-files", "-z", "--cached", "--others", "--exclude-standard"],
+            "README.md\0src/main.rs\0notes with space.txt\0",
+        ));
+
+        let files = with_runner(runner, || list_files(Path::new("/repo")).unwrap());
+        assert_eq!(
+            files,
+            vec!["README.md", "src/main.rs", "notes with space.txt"]
+        );
+    }
+
+    #[test]
+    fn test_list_ignored_with_mock() {
+        let runner = Rc::new(MockGitRunner::default().ok(
+            &[
+                "ls-files",
+                "-z",
+                "--others",
+                "--ignored",
+                "--exclude-standard",
+                "--directory",
+            ],
+            ".env\0node_modules/\0",
+        ));
+
+        let files = with_runner(runner, || list_ignored(Path::new("/repo")).unwrap());
+        assert_eq!(files, vec![".env", "node_modules/"]);
+    }
+
+    #[test]
+    fn test_rev_parse_with_mock() {
+        let runner = Rc::new(MockGitRunner::default().ok(&["rev-parse", "HEAD"], "abc123\n"));
+
+        let sha = with_runner(runner, || rev_parse("HEAD", Path::new("/repo")).unwrap());
+        assert_eq!(sha, "abc123");
+    }
+
+    #[test]
+    fn test_merged_branches_with_mock() {
+        let runner = Rc::new(MockGitRunner::default().ok(
+            &["branch", "--merged", "main", "--format=%(refname:short)"],
+            "main\ngj/20260205_done\n",
+        ));
+
+        let branches = with_runner(runner, || {
+            merged_branches("main", Path::new("/repo")).unwrap()
+        });
+        assert_eq!(branches, vec!["main", "gj/20260205_done"]);
+    }
+
+    #[test]
+    fn test_check_branch_name() {
+        assert!(check_branch_name("hotfix/20260205_fix").is_ok());
+        assert!(check_branch_name("hotfix..x/20260205_fix").is_err());
+        assert!(check_branch_name("hotfix/.lock/fix").is_err());
+    }
+
+    #[test]
+    fn test_add_orphan_worktree_with_mock() {
+        let runner = Rc::new(MockGitRunner::default().ok(
+            &[
+                "worktree",
+                "add",
+                "--orphan",
+                "-b",
+                "gj/scratch",
+                "/wt/scratch",
+            ],
+            "",
+        ));
+
+        with_runner(runner.clone(), || {
+            add_orphan_worktree(Path::new("/wt/scratch"), "gj/scratch", true).unwrap()
+        });
+        assert_eq!(runner.calls().len(), 1);
+    }
+
+    #[test]
+    fn test_add_orphan_worktree_fallback_with_mock() {
+        let runner = Rc::new(
+            MockGitRunner::default()
+                .ok(&["worktree", "add", "--detach", "/wt/scratch"], "")
+                .ok(&["checkout", "--orphan", "gj/scratch"], "")
+                .ok(&["rm", "-rf", "--quiet", "--ignore-unmatch", "."], ""),
+        );
+
+        with_runner(runner.clone(), || {
+            add_orphan_worktree(Path::new("/wt/scratch"), "gj/scratch", false).unwrap()
+        });
+        assert_eq!(runner.calls().len(), 3);
+    }
+
+    #[test]
+    fn test_upstream_with_mock() {
+        let rev_parse = ["rev-parse", "--abbrev-ref", "feature@{upstream}"];
+        let for_each_ref = [
+            "for-each-ref",
+            "--format=%(upstream:short)",
+            "refs/heads/feature",
+        ];
+        let repo = Path::new("/repo");
+
+        let runner = Rc::new(MockGitRunner::default().ok(&rev_parse, "origin/feature\n"));
+        let result = with_runner(runner, || upstream("feature", repo).unwrap());
+        assert_eq!(result, Upstream::Tracking("origin/feature".to_string()));
+
+        let runner = Rc::new(
+            MockGitRunner::default()
+                .fail(&rev_parse, "fatal: no such branch")
+                .ok(&for_each_ref, "origin/feature\n"),
+        );
+        let result = with_runner(runner, || upstream("feature", repo).unwrap());
+        assert_eq!(result, Upstream::Gone("origin/feature".to_string()));
+
+        let runner = Rc::new(
+            MockGitRunner::default()
+                .fail(&rev_parse, "fatal: no upstream configured")
+                .ok(&for_each_ref, "\n"),
+        );
+        let result = with_runner(runner, || upstream("feature", repo).unwrap());
+        assert_eq!(result, Upstream::None);
+    }
+
+    #[test]
+    fn test_ahead_behind_with_mock() {
+        let args = ["rev-list", "--left-right", "--count", "@{upstream}...HEAD"];
+        let runner = Rc::new(MockGitRunner::default().ok(&args, "3\t5\n"));
+        let counts = with_runner(runner, || ahead_behind(Path::new("/repo")).unwrap());
+        assert_eq!(counts, Some((5, 3)));
+
+        let runner = Rc::new(MockGitRunner::default().fail(&args, "fatal: no upstream configured"));
+        let counts = with_runner(runner, || ahead_behind(Path::new("/repo")).unwrap());
+        assert_eq!(counts, None);
+    }
+
+    #[test]
+    fn test_push_set_upstream_with_mock() {
+        let args = ["push", "--set-upstream", "origin", "feature"];
+        let runner = Rc::new(MockGitRunner::default().ok(&args, ""));
+        with_runner(runner.clone(), || {
+            push_set_upstream("feature", Path::new("/wt/feature")).unwrap()
+        });
+        assert_eq!(runner.calls(), vec![args.to_vec()]);
+
+        let runner = Rc::new(MockGitRunner::default().fail(
+            &args,
+            "remote: error: GH006: Protected branch update failed",
+        ));
+        let err = with_runner(runner, || {
+            push_set_upstream("feature", Path::new("/wt/feature")).unwrap_err()
+        });
+        assert!(err.to_string().contains("Protected branch"));
+    }
+
+    #[test]
+    fn test_parse_left_right_count() {
+        assert_eq!(parse_left_right_count("0\t2\n"), Some((0, 2)));
+        assert_eq!(parse_left_right_count(""), None);
+        assert_eq!(parse_left_right_count("x\t1"), None);
+    }
+
+    #[test]
+    fn test_ref_exists_with_mock() {
+        let runner = Rc::new(
+            MockGitRunner::default()
+                .ok(
+                    &["rev-parse", "--verify", "--quiet", "develop^{commit}"],
+                    "abc123\n",
+                )
+                .fail(&["rev-parse", "--verify", "--quiet", "nope^{commit}"], ""),
+        );
+
+        with_runner(runner, || {
+            assert!(ref_exists("develop", Path::new("/repo")).unwrap());
+            assert!(!ref_exists("nope", Path::new("/repo")).unwrap());
+        });
+    }
+
+    #[test]
+    fn test_get_default_branch_falls_back_to_master_with_mock() {
+        let runner = Rc::new(
+            MockGitRunner::default()
+                .fail(
+                    &["symbolic-ref", "refs/remotes/origin/HEAD"],
+                    "not a symbolic ref",
+                )
+                .fail(&["rev-parse", "--verify", "refs/heads/main"], "")
+                .ok(&["rev-parse", "--verify", "refs/heads/master"], "abc\n"),
+        );
+
+        let branch = with_runner(runner, || get_default_branch(Path::new("/repo")).unwrap());
+        assert_eq!(branch, "master");
+    }
+
+    #[test]
+    fn test_git_checked_includes_stderr_with_mock() {
+        let runner = Rc::new(
+            MockGitRunner::default()
+                .fail(&["merge", "--abort"], "fatal: There is no merge to abort\n"),
+        );
+
+        let err = with_runner(runner, || merge_abort(Path::new("/repo"))).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Failed to abort merge: fatal: There is no merge to abort"
+        );
+    }
+
+    #[test]
+    fn test_rebase_helpers_with_mock() {
+        let runner = Rc::new(
+            MockGitRunner::default()
+                .fail(
+                    &["rebase", "main"],
+                    "CONFLICT (content): Merge conflict in a\n",
+                )
+                .ok(&["rebase", "--abort"], "")
+                .ok(&["merge", "--ff-only", "gj/feature"], ""),
+        );
+
+        with_runner(runner.clone(), || {
+            let err = rebase_onto("main", Path::new("/wt")).unwrap_err();
+            assert!(err
+                .to_string()
+                .starts_with("Failed to rebase onto main: CONFLICT"));
+            rebase_abort(Path::new("/wt")).unwrap();
+            merge_branch_ff_only("gj/feature", Path::new("/repo")).unwrap();
+        });
+        assert_eq!(runner.calls().len(), 3);
+    }
+
+    #[test]
+    fn test_parse_git_version() {
+        assert_eq!(parse_git_version("git version 2.39.5"), Some((2, 39)));
+        assert_eq!(
+            parse_git_version("git version 2.39.3 (Apple Git-146)"),
+            Some((2, 39))
+        );
+        assert_eq!(
+            parse_git_version("git version 2.45.1.windows.1"),
+            Some((2, 45))
+        );
+        assert_eq!(parse_git_version("git version 1.9"), Some((1, 9)));
+        assert_eq!(parse_git_version("not git"), None);
+        assert!(parse_git_version("git version 2.5.0").unwrap() < MIN_GIT_VERSION);
+    }
+
+    #[test]
+    fn test_classify_gh_error_not_authenticated() {
+        assert_eq!(
+            classify_gh_error(
+                "To get started with GitHub CLI, please run:  gh auth login\n\
+                 Alternatively, populate the GH_TOKEN environment variable with a GitHub API authentication token."
+            ),
+            Some(GhError::NotAuthenticated)
+        );
+        assert_eq!(
+            classify_gh_error(
+                "You are not logged into any GitHub hosts. Run gh auth login to authenticate."
+            ),
+            Some(GhError::NotAuthenticated)
+        );
+        assert_eq!(
+            classify_gh_error("HTTP 401: Bad credentials (https://api.github.com/graphql)"),
+            Some(GhError::NotAuthenticated)
+        );
+    }
+
+    #[test]
+    fn test_classify_gh_error_pr_not_found() {
+        assert_eq!(
+            classify_gh_error(
+                "GraphQL: Could not resolve to a PullRequest with the number of 9999. (repository.pullRequest)"
+            ),
+            Some(GhError::PrNotFound)
+        );
+    }
+
+    #[test]
+    fn test_classify_gh_error_unknown() {
+        assert_eq!(
+            classify_gh_error("error connecting to api.github.com"),
+            None
+        );
+        assert_eq!(classify_gh_error(""), None);
+    }
+
+    #[test]
+    fn test_parse_pr_list() {
+        let json = br#"[
+            {"number": 42, "title": "Fix bug", "headRefName": "fix-bug"},
+            {"number": 7, "title": "Add feature", "headRefName": "feature/add"}
+        ]"#;
+        let prs = parse_pr_list(json).unwrap();
+        assert_eq!(prs.len(), 2);
+        assert_eq!(
+            prs[0],
+            PrSummary {
+                number: 42,
+                title: "Fix bug".to_string(),
+                head_ref_name: "fix-bug".to_string(),
+            }
+        );
+        assert_eq!(prs[1].head_ref_name, "feature/add");
+    }
+
+    #[test]
+    fn test_parse_pr_head_same_repository() {
+        let json = br#"{"headRefName":"fix-bug","isCrossRepository":false,
+            "headRepositoryOwner":{"id":"MDQ6VXNlcjE=","login":"mirakui"}}"#;
+        let head = parse_pr_head(json).unwrap();
+        assert_eq!(head.branch, "fix-bug");
+        assert!(!head.is_cross_repository);
+        assert_eq!(head.local_branch(), "fix-bug");
+    }
+
+    #[test]
+    fn test_parse_pr_head_fork() {
+        let json = br#"{"headRefName":"main","isCrossRepository":true,
+            "headRepositoryOwner":{"id":"MDQ6VXNlcjI=","login":"contributor"}}"#;
+        let head = parse_pr_head(json).unwrap();
+        assert!(head.is_cross_repository);
+        assert_eq!(head.local_branch(), "contributor/main");
+    }
+
+    #[test]
+    fn test_parse_pr_head_deleted_fork() {
+        // The head repository owner is null when the fork has been deleted
+        let json = br#"{"headRefName":"patch-1","isCrossRepository":true,
+            "headRepositoryOwner":null}"#;
+        assert_eq!(parse_pr_head(json).unwrap().local_branch(), "patch-1");
+    }
+
+    #[test]
+    fn test_api_pull_url() {
+        let repo = parse_github_url("git@github.com:mirakui/gj.git").unwrap();
+        assert_eq!(
+            api_pull_url(&repo, 7),
+            "https://api.github.com/repos/mirakui/gj/pulls/7"
+        );
+
+        let repo = parse_github_url("https://ghe.example.com/team/app.git").unwrap();
+        assert_eq!(
+            api_pull_url(&repo, 7),
+            "https://ghe.example.com/api/v3/repos/team/app/pulls/7"
+        );
+    }
+
+    #[test]
+    fn test_parse_api_pull() {
+        let json = br#"{"number":7,
+            "head":{"ref":"fix-bug","repo":{"full_name":"mirakui/gj","owner":{"login":"mirakui"}}},
+            "base":{"ref":"main","repo":{"full_name":"mirakui/gj","owner":{"login":"mirakui"}}}}"#;
+        let head = parse_api_pull(json).unwrap();
+        assert_eq!(head.branch, "fix-bug");
+        assert!(!head.is_cross_repository);
+        assert_eq!(head.local_branch(), "fix-bug");
+
+        let json = br#"{"number":8,
+            "head":{"ref":"main","repo":{"full_name":"contributor/gj","owner":{"login":"contributor"}}},
+            "base":{"ref":"main","repo":{"full_name":"mirakui/gj","owner":{"login":"mirakui"}}}}"#;
+        assert_eq!(
+            parse_api_pull(json).unwrap().local_branch(),
+            "contributor/main"
+        );
+
+        // The head repository is null when the fork has been deleted
+        let json = br#"{"number":9,"head":{"ref":"patch-1","repo":null},
+            "base":{"ref":"main","repo":{"full_name":"mirakui/gj","owner":{"login":"mirakui"}}}}"#;
+        let head = parse_api_pull(json).unwrap();
+        assert!(head.is_cross_repository);
+        assert_eq!(head.local_branch(), "patch-1");
+    }
+
+    #[test]
+    fn test_format_command_line() {
+        let mut command = Command::new("git");
+        command
+            .args(["commit", "-m", "it's done", "--", "a b.txt", "src/main.rs"])
+            .current_dir("/repo");
+        assert_eq!(
+            format_command_line(&command),
+            "+ git commit -m 'it'\\''s done' -- 'a b.txt' src/main.rs  (in /repo)"
+        );
+
+        let mut command = Command::new("gh");
+        command.args(["pr", "view", ""]);
+        assert_eq!(format_command_line(&command), "+ gh pr view ''");
+    }
+
+    #[test]
+    fn test_fetch_pull_head() {
+        let runner = Rc::new(MockGitRunner::default().ok(
+            &[
+                "fetch",
+                "origin",
+                "+refs/pull/42/head:refs/remotes/origin/pull/42/head",
+            ],
+            "",
+        ));
+
+        with_runner(runner.clone(), || fetch_pull_head(42).unwrap());
+        assert_eq!(runner.calls().len(), 1);
+    }
+
+    #[test]
+    fn test_parse_pr_list_empty() {
+        assert!(parse_pr_list(b"[]").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_pr_url() {
+        assert_eq!(
+            pr_url("mirakui", "gj", 42, GITHUB_HOST),
+            "https://github.com/mirakui/gj/pull/42"
+        );
+
+        let repo = parse_github_url("git@ghe.example.com:team/app.git").unwrap();
+        assert_eq!(
+            pr_url(&repo.owner, &repo.repo, 7, &repo.host),
+            "https://ghe.example.com/team/app/pull/7"
+        );
+    }
+
     #[test]
     fn test_parse_github_url_ssh() {
         let result = parse_github_url("git@github.com:mirakui/my_repo.git").unwrap();
         assert_eq!(
             result,
             GitHubRepo {
+                host: "github.com".to_string(),
                 owner: "mirakui".to_string(),
                 repo: "my_repo".to_string()
             }
@@ -776,18 +2213,86 @@ mod tests {
         assert_eq!(
             result,
             GitHubRepo {
+                host: "github.com".to_string(),
                 owner: "mirakui".to_string(),
                 repo: "my_repo".to_string()
             }
         );
     }
 
+    #[test]
+    fn test_clear_repo_env_ignores_ambient_git_dir() {
+        let repo = create_temp_git_repo();
+        let other = create_temp_git_repo();
+        let worktree = repo.path().join("wt");
+        for dir in [repo.path(), other.path()] {
+            let output = Command::new("git")
+                .args(["commit", "--allow-empty", "-m", "initial"])
+                .current_dir(dir)
+                .output()
+                .unwrap();
+            assert!(output.status.success());
+        }
+
+        // As inside a git hook of `other`
+        let mut command = Command::new("git");
+        command
+            .args(["worktree", "add", "--detach"])
+            .arg(&worktree)
+            .current_dir(repo.path())
+            .env("GIT_DIR", other.path().join(".git"))
+            .env("GIT_WORK_TREE", other.path());
+        let output = clear_repo_env(&mut command).output().unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let list = |dir: &Path| {
+            let output = Command::new("git")
+                .args(["worktree", "list", "--porcelain"])
+                .current_dir(dir)
+                .output()
+                .unwrap();
+            String::from_utf8_lossy(&output.stdout).into_owned()
+        };
+        assert!(list(repo.path()).contains("/wt\n"));
+        assert!(!list(other.path()).contains("/wt\n"));
+    }
+
+    #[test]
+    fn test_parse_worktree_list() {
+        let stdout = "worktree /repo\nHEAD 1111\nbranch refs/heads/main\n\n\
+            worktree /wt/feature\nHEAD 2222\nbranch refs/heads/feature/x\n\n\
+            worktree /wt/detached\nHEAD 3333\ndetached\n\n";
+        let worktrees = parse_worktree_list(stdout);
+        assert_eq!(worktrees.len(), 3);
+        assert_eq!(worktrees[0].path, PathBuf::from("/repo"));
+        assert_eq!(worktrees[1].branch.as_deref(), Some("feature/x"));
+        assert_eq!(worktrees[2].branch, None);
+    }
+
+    #[test]
+    fn test_in_progress_operation() {
+        let temp_dir = create_temp_git_repo();
+        let repo_path = temp_dir.path();
+        assert_eq!(in_progress_operation(repo_path).unwrap(), None);
+
+        fs::write(repo_path.join(".git/MERGE_HEAD"), "0000\n").unwrap();
+        assert_eq!(in_progress_operation(repo_path).unwrap(), Some("merge"));
+
+        fs::create_dir(repo_path.join(".git/rebase-merge")).unwrap();
+        assert_eq!(in_progress_operation(repo_path).unwrap(), Some("rebase"));
+    }
+
     #[test]
     fn test_parse_github_url_https() {
         let result = parse_github_url("https://github.com/mirakui/my_repo.git").unwrap();
         assert_eq!(
             result,
             GitHubRepo {
+                host: "github.com".to_string(),
                 owner: "mirakui".to_string(),
                 repo: "my_repo".to_string()
             }
@@ -800,6 +2305,7 @@ mod tests {
         assert_eq!(
             result,
             GitHubRepo {
+                host: "github.com".to_string(),
                 owner: "mirakui".to_string(),
                 repo: "my_repo".to_string()
             }
@@ -812,6 +2318,7 @@ mod tests {
         assert_eq!(
             result,
             GitHubRepo {
+                host: "github.com".to_string(),
                 owner: "mirakui".to_string(),
                 repo: "my_repo".to_string()
             }
@@ -819,13 +2326,31 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_github_url_unsupported_host() {
-        let result = parse_github_url("git@gitlab.com:mirakui/my_repo.git");
-        assert!(result.is_err());
-        assert!(result
-            .unwrap_err()
-            .to_string()
-            .contains("Only GitHub repositories"));
+    fn test_parse_github_url_enterprise_host() {
+        let expected = GitHubRepo {
+            host: "ghe.example.com".to_string(),
+            owner: "team".to_string(),
+            repo: "app".to_string(),
+        };
+        for url in [
+            "git@ghe.example.com:team/app.git",
+            "https://ghe.example.com/team/app.git",
+            "https://user@GHE.example.com/team/app/",
+            "ssh://git@ghe.example.com:2222/team/app.git",
+        ] {
+            assert_eq!(parse_github_url(url).unwrap(), expected, "{}", url);
+        }
+    }
+
+    #[test]
+    fn test_parse_github_url_unsupported_format() {
+        for url in ["/srv/git/app.git", "file:///srv/git/app.git", "./repo:app"] {
+            let result = parse_github_url(url);
+            assert!(result
+                .unwrap_err()
+                .to_string()
+                .contains("Unsupported remote URL format"));
+        }
     }
 
     #[test]
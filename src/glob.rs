@@ -0,0 +1,46 @@
+/// Match text against a shell-style wildcard pattern (`*` and `?`)
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` in the pattern and the text position it matched up to
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            // Let the last `*` consume one more character
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("pr-*", "pr-123"));
+        assert!(glob_match("gj/*_fix*", "gj/20260205_fix-login"));
+        assert!(glob_match("feature/?", "feature/a"));
+        assert!(glob_match("*", "anything"));
+        assert!(glob_match("exact", "exact"));
+
+        assert!(!glob_match("pr-*", "gj/20260205_pr"));
+        assert!(!glob_match("feature/?", "feature/ab"));
+        assert!(!glob_match("exact", "exactly"));
+    }
+}
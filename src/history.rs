@@ -0,0 +1,127 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::state::WorktreeState;
+
+/// What happened to a worktree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventKind {
+    /// Created by `gj new`, `gj pr` or `gj checkout`
+    Create,
+    /// Removed by `gj exit`
+    Exit,
+}
+
+/// One line of the activity log
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Event {
+    pub event: EventKind,
+    pub at: DateTime<Utc>,
+    /// Absolute path to the origin repository
+    pub repo: PathBuf,
+    pub branch: String,
+    pub worktree_path: PathBuf,
+}
+
+impl Event {
+    /// An event for a worktree, happening now
+    pub fn new(event: EventKind, state: &WorktreeState) -> Self {
+        Event {
+            event,
+            at: Utc::now(),
+            repo: state.origin_repo.clone(),
+            branch: state.branch.clone(),
+            worktree_path: state.worktree_path.clone(),
+        }
+    }
+}
+
+/// Get the activity log path (~/.gj/history.jsonl)
+pub fn history_path() -> Result<PathBuf> {
+    if let Ok(path) = std::env::var("GJ_HISTORY_FILE") {
+        if !path.is_empty() {
+            return Ok(PathBuf::from(path));
+        }
+    }
+    let home_dir = dirs::home_dir().context("Could not determine home directory")?;
+    Ok(home_dir.join(".gj").join("history.jsonl"))
+}
+
+/// Append an event to the log
+fn append(path: &Path, event: &Event) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+    let line = serde_json::to_string(event).context("Failed to serialize history event")?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open history file: {}", path.display()))?;
+    writeln!(file, "{}", line)
+        .with_context(|| format!("Failed to write history file: {}", path.display()))
+}
+
+/// Record an event for a worktree; the log is best-effort, so a failure only warns
+pub fn record(event: EventKind, state: &WorktreeState) {
+    let result = history_path().and_then(|path| append(&path, &Event::new(event, state)));
+    if let Err(e) = result {
+        eprintln!("Warning: Failed to record history: {:#}", e);
+    }
+}
+
+/// Parse log lines, skipping blank and malformed ones (e.g. a line cut short by a crash)
+pub fn parse_events(content: &str) -> Vec<Event> {
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// Read all events; a missing log has none
+pub fn read_events() -> Result<Vec<Event>> {
+    let path = history_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read history file: {}", path.display()))?;
+    Ok(parse_events(&content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_and_parse_events() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("nested").join("history.jsonl");
+        let state = WorktreeState::new(
+            PathBuf::from("/wt/feature"),
+            PathBuf::from("/repo"),
+            "feature".to_string(),
+        );
+
+        append(&path, &Event::new(EventKind::Create, &state)).unwrap();
+        append(&path, &Event::new(EventKind::Exit, &state)).unwrap();
+
+        // A truncated trailing line is ignored
+        let mut content = fs::read_to_string(&path).unwrap();
+        content.push_str("{\"event\":\"create\",\"at\":\n");
+
+        let events = parse_events(&content);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event, EventKind::Create);
+        assert_eq!(events[1].event, EventKind::Exit);
+        assert_eq!(events[1].branch, "feature");
+        assert_eq!(events[1].repo, PathBuf::from("/repo"));
+    }
+}
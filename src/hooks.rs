@@ -1,23 +1,99 @@
 use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
-use std::process::Command;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
 
 use crate::config::Hook;
+use crate::glob::glob_match;
 
-/// Execute hooks after worktree creation
-pub fn execute_hooks(hooks: &[&Hook], origin_repo: &Path, worktree_path: &Path) -> Result<()> {
-    for hook in hooks {
-        match hook {
-            Hook::Copy { from, to, required } => {
-                execute_copy_hook(from, to.as_deref(), *required, origin_repo, worktree_path)?;
-            }
-            Hook::Run { command } => {
-                execute_run_hook(command, worktree_path)?;
-            }
+/// Execute hooks after worktree creation.
+/// Serially, stops at the first failure; in parallel, runs all hooks and reports every failure.
+/// Run hooks get `env` (`hook_env`) added to their environment.
+pub fn execute_hooks(
+    hooks: &[&Hook],
+    origin_repo: &Path,
+    worktree_path: &Path,
+    env: &HashMap<String, String>,
+    parallel: bool,
+) -> Result<()> {
+    if !parallel {
+        for hook in hooks {
+            execute_hook(hook, origin_repo, worktree_path, env)?;
+        }
+        return Ok(());
+    }
+
+    let workers = thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(hooks.len());
+    let next = AtomicUsize::new(0);
+    let failures = Mutex::new(Vec::new());
+
+    // Each worker takes the next unstarted hook until none are left
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::SeqCst);
+                let Some(hook) = hooks.get(index) else {
+                    break;
+                };
+                if let Err(e) = execute_hook(hook, origin_repo, worktree_path, env) {
+                    failures.lock().unwrap().push((index, e));
+                }
+            });
+        }
+    });
+
+    let mut failures = failures.into_inner().unwrap();
+    failures.sort_by_key(|(index, _)| *index);
+
+    match failures.len() {
+        0 => Ok(()),
+        1 => Err(failures.remove(0).1),
+        n => bail!(
+            "{} hooks failed:\n{}",
+            n,
+            failures
+                .iter()
+                .map(|(_, e)| format!("  - {:#}", e))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ),
+    }
+}
+
+/// Execute a single hook
+fn execute_hook(
+    hook: &Hook,
+    origin_repo: &Path,
+    worktree_path: &Path,
+    env: &HashMap<String, String>,
+) -> Result<()> {
+    match hook {
+        Hook::Copy {
+            from,
+            to,
+            required,
+            exclude,
+        } => execute_copy_hook(
+            from,
+            to.as_deref(),
+            *required,
+            exclude,
+            origin_repo,
+            worktree_path,
+        ),
+        Hook::Run { command, timeout } => {
+            let timeout = timeout.map(Duration::from_secs);
+            execute_run_hook(command, timeout, worktree_path, env)
         }
     }
-    Ok(())
 }
 
 /// Execute a copy hook
@@ -25,12 +101,13 @@ fn execute_copy_hook(
     from: &str,
     to: Option<&str>,
     required: bool,
+    exclude: &[String],
     origin_repo: &Path,
     worktree_path: &Path,
 ) -> Result<()> {
     let source = origin_repo.join(from);
-    let dest_name = to.unwrap_or(from);
-    let dest = worktree_path.join(dest_name);
+    let dest_name = copy_dest_name(from, to);
+    let dest = worktree_path.join(&dest_name);
 
     if !source.exists() {
         if required {
@@ -50,25 +127,290 @@ fn execute_copy_hook(
             .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
     }
 
-    fs::copy(&source, &dest)
-        .with_context(|| format!("Failed to copy {} to {}", source.display(), dest.display()))?;
+    if source.is_dir() {
+        copy_dir(&source, &dest, Path::new(""), exclude)?;
+    } else {
+        copy_file(&source, &dest)?;
+    }
 
     eprintln!("Copied: {} -> {}", from, dest_name);
 
     Ok(())
 }
 
-/// Execute a run hook
-fn execute_run_hook(command: &str, worktree_path: &Path) -> Result<()> {
+/// Destination of a copy hook relative to the worktree: `to` (default: `from`).
+/// A `to` ending with `/` is a directory that receives `from`'s basename.
+fn copy_dest_name(from: &str, to: Option<&str>) -> String {
+    match to {
+        Some(dir) if dir.ends_with('/') => {
+            let basename = Path::new(from)
+                .file_name()
+                .map(|name| name.to_string_lossy())
+                .unwrap_or_default();
+            format!("{}{}", dir, basename)
+        }
+        Some(to) => to.to_string(),
+        None => from.to_string(),
+    }
+}
+
+/// Recursively copy a directory, skipping entries that match an `exclude` pattern.
+/// `relative` is the path of `source` below the top-level copied directory.
+fn copy_dir(source: &Path, dest: &Path, relative: &Path, exclude: &[String]) -> Result<()> {
+    fs::create_dir_all(dest)
+        .with_context(|| format!("Failed to create directory: {}", dest.display()))?;
+
+    let entries = fs::read_dir(source)
+        .with_context(|| format!("Failed to read directory: {}", source.display()))?;
+    for entry in entries {
+        let entry = entry?;
+        let relative = relative.join(entry.file_name());
+        if is_excluded(&relative, exclude) {
+            continue;
+        }
+
+        let file_type = entry.file_type()?;
+        copy_entry(
+            &entry.path(),
+            file_type,
+            &dest.join(entry.file_name()),
+            &relative,
+            exclude,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Copy one entry found while walking a directory. Symlinks are re-created rather than
+/// followed, so a link pointing back up the tree cannot recurse and a link to a large
+/// tree elsewhere is not copied in full.
+fn copy_entry(
+    source: &Path,
+    file_type: fs::FileType,
+    dest: &Path,
+    relative: &Path,
+    exclude: &[String],
+) -> Result<()> {
+    if file_type.is_symlink() {
+        copy_symlink(source, dest)
+    } else if file_type.is_dir() {
+        copy_dir(source, dest, relative, exclude)
+    } else {
+        copy_file(source, dest)
+    }
+}
+
+/// Create a symlink at `dest` with the same target as the one at `source`
+fn copy_symlink(source: &Path, dest: &Path) -> Result<()> {
+    let target = fs::read_link(source)
+        .with_context(|| format!("Failed to read symlink: {}", source.display()))?;
+    if fs::symlink_metadata(dest).is_ok() {
+        fs::remove_file(dest).with_context(|| format!("Failed to replace {}", dest.display()))?;
+    }
+
+    #[cfg(unix)]
+    let created = std::os::unix::fs::symlink(&target, dest);
+    #[cfg(not(unix))]
+    let created: std::io::Result<()> = Err(std::io::ErrorKind::Unsupported.into());
+    created.with_context(|| {
+        format!(
+            "Failed to create symlink {} -> {}",
+            dest.display(),
+            target.display()
+        )
+    })
+}
+
+/// Copy a file, then set the source's permissions explicitly so modes such as the
+/// executable bit survive regardless of how the platform's copy treats them
+fn copy_file(source: &Path, dest: &Path) -> Result<()> {
+    fs::copy(source, dest)
+        .with_context(|| format!("Failed to copy {} to {}", source.display(), dest.display()))?;
+    let permissions = fs::metadata(source)
+        .with_context(|| format!("Failed to read metadata: {}", source.display()))?
+        .permissions();
+    fs::set_permissions(dest, permissions)
+        .with_context(|| format!("Failed to set permissions: {}", dest.display()))
+}
+
+/// Whether a path inside a copied directory matches any `exclude` pattern,
+/// by its path relative to the copied directory or by its file name
+fn is_excluded(relative: &Path, exclude: &[String]) -> bool {
+    let path = relative.to_string_lossy();
+    let name = relative
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    exclude
+        .iter()
+        .any(|pattern| glob_match(pattern, &path) || glob_match(pattern, &name))
+}
+
+/// Copy the origin repository's ignored, untracked files (such as `.env`) into a new
+/// worktree (`default.copy_gitignored`), skipping paths that match an `exclude` pattern
+pub fn copy_gitignored(origin_repo: &Path, worktree_path: &Path, exclude: &[String]) -> Result<()> {
+    let entries = crate::git::list_ignored(origin_repo)?;
+    let copied = copy_ignored_entries(&entries, origin_repo, worktree_path, exclude);
+    if copied > 0 {
+        eprintln!(
+            "Copied {} ignored path(s) from the origin repository",
+            copied
+        );
+    }
+    Ok(())
+}
+
+/// Copy `entries` (as listed by `git::list_ignored`) from `origin_repo` to `worktree_path`,
+/// returning how many were copied. A failed entry only warns, so one unreadable file
+/// doesn't keep the rest from being copied.
+fn copy_ignored_entries(
+    entries: &[String],
+    origin_repo: &Path,
+    worktree_path: &Path,
+    exclude: &[String],
+) -> usize {
+    let mut copied = 0;
+    for entry in entries {
+        let relative = Path::new(entry.trim_end_matches('/'));
+        let source = origin_repo.join(relative);
+        // Worktrees kept inside the repository are usually ignored; don't copy one into itself
+        if is_excluded(relative, exclude) || worktree_path.starts_with(&source) {
+            continue;
+        }
+
+        let dest = worktree_path.join(relative);
+        let result = match dest.parent() {
+            Some(parent) => fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display())),
+            None => Ok(()),
+        }
+        .and_then(|()| {
+            let metadata = fs::symlink_metadata(&source)
+                .with_context(|| format!("Failed to read metadata: {}", source.display()))?;
+            copy_entry(&source, metadata.file_type(), &dest, relative, exclude)
+        });
+        match result {
+            Ok(()) => copied += 1,
+            Err(e) => eprintln!("Warning: Could not copy {}: {:#}", entry, e),
+        }
+    }
+    copied
+}
+
+/// Environment variable naming a file the shell wrapper runs with `sh` after it cds
+pub const AFTER_CD_FILE_ENV: &str = "GJ_AFTER_CD_FILE";
+
+/// Run after_exit hooks for the directory `gj exit` returns to.
+/// With the shell wrapper active, the commands are handed to it to run after its `cd`;
+/// otherwise they run here in `target_dir` with `env`. Copy hooks are ignored.
+/// A hook with a `timeout` needs gj to watch it, so then all of them run here.
+pub fn execute_after_exit_hooks(
+    hooks: &[&Hook],
+    target_dir: &Path,
+    env: &HashMap<String, String>,
+    use_wrapper: bool,
+) -> Result<()> {
+    let commands: Vec<&str> = hooks
+        .iter()
+        .filter_map(|hook| match hook {
+            Hook::Run { command, .. } => Some(command.as_str()),
+            Hook::Copy { .. } => None,
+        })
+        .collect();
+    if commands.is_empty() {
+        return Ok(());
+    }
+
+    let timed = hooks.iter().any(|hook| {
+        matches!(
+            hook,
+            Hook::Run {
+                timeout: Some(_),
+                ..
+            }
+        )
+    });
+    if use_wrapper && !timed {
+        if let Some(file) = std::env::var_os(AFTER_CD_FILE_ENV).filter(|f| !f.is_empty()) {
+            return fs::write(&file, after_cd_script(&commands, env))
+                .with_context(|| format!("Failed to write {}", Path::new(&file).display()));
+        }
+    }
+
+    for hook in hooks {
+        if let Hook::Run { command, timeout } = hook {
+            execute_run_hook(command, timeout.map(Duration::from_secs), target_dir, env)?;
+        }
+    }
+    Ok(())
+}
+
+/// Script the shell wrapper runs after `cd`: `export` lines for `env`, then one
+/// command per line
+fn after_cd_script(commands: &[&str], env: &HashMap<String, String>) -> String {
+    let mut names: Vec<&String> = env.keys().collect();
+    names.sort();
+    let exports = names
+        .into_iter()
+        .map(|name| format!("export {}={}\n", name, crate::shell::single_quote(&env[name])));
+    let commands = commands.iter().map(|command| format!("{}\n", command));
+    exports.chain(commands).collect()
+}
+
+/// Interval between checks on a run hook with a timeout
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Execute a run hook, killing it if it exceeds the timeout
+fn execute_run_hook(
+    command: &str,
+    timeout: Option<Duration>,
+    worktree_path: &Path,
+    env: &HashMap<String, String>,
+) -> Result<()> {
     eprintln!("Running: {}", command);
 
-    let status = Command::new("sh")
-        .arg("-c")
+    let mut sh = Command::new("sh");
+    sh.arg("-c")
         .arg(command)
         .current_dir(worktree_path)
-        .status()
+        .envs(env);
+    // stdout is reserved for the worktree path read by the shell wrapper
+    sh.stdout(std::io::stderr());
+    // A hook that may be killed gets its own process group, so that whatever it
+    // started (npm, docker compose, ...) can be killed along with it
+    #[cfg(unix)]
+    if timeout.is_some() {
+        use std::os::unix::process::CommandExt;
+        sh.process_group(0);
+    }
+    let started = Instant::now();
+    let mut child = crate::git::traced(&mut sh)
+        .spawn()
         .with_context(|| format!("Failed to execute command: {}", command))?;
 
+    let status = match timeout {
+        None => child.wait(),
+        Some(timeout) => loop {
+            if let Some(status) = child.try_wait()? {
+                break Ok(status);
+            }
+            if started.elapsed() >= timeout {
+                kill_hook(&mut child);
+                let _ = child.wait();
+                crate::debug_log::process(&sh, started, None);
+                bail!(
+                    "Hook command timed out after {}s: {}",
+                    timeout.as_secs(),
+                    command
+                );
+            }
+            thread::sleep(POLL_INTERVAL);
+        },
+    }
+    .with_context(|| format!("Failed to wait for command: {}", command))?;
+    crate::debug_log::process(&sh, started, status.code());
+
     if !status.success() {
         bail!("Hook command failed: {}", command);
     }
@@ -76,6 +418,19 @@ fn execute_run_hook(command: &str, worktree_path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Kill a timed-out hook and the processes it started (its process group on unix)
+fn kill_hook(child: &mut Child) {
+    #[cfg(unix)]
+    {
+        let group = format!("-{}", child.id());
+        let _ = Command::new("kill")
+            .args(["-s", "KILL", "--", &group])
+            .stderr(Stdio::null())
+            .status();
+    }
+    let _ = child.kill();
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -90,13 +445,49 @@ mod tests {
         let source_file = origin.path().join(".env");
         fs::write(&source_file, "TEST=value").unwrap();
 
-        execute_copy_hook(".env", None, false, origin.path(), worktree.path()).unwrap();
+        execute_copy_hook(".env", None, false, &[], origin.path(), worktree.path()).unwrap();
 
         let dest_file = worktree.path().join(".env");
         assert!(dest_file.exists());
         assert_eq!(fs::read_to_string(&dest_file).unwrap(), "TEST=value");
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_copy_hook_preserves_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let origin = TempDir::new().unwrap();
+        let worktree = TempDir::new().unwrap();
+
+        fs::create_dir_all(origin.path().join("bin")).unwrap();
+        let script = origin.path().join("bin/setup");
+        fs::write(&script, "#!/bin/sh\n").unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o751)).unwrap();
+        let secret = origin.path().join("bin/secret");
+        fs::write(&secret, "token").unwrap();
+        fs::set_permissions(&secret, fs::Permissions::from_mode(0o600)).unwrap();
+
+        execute_copy_hook("bin/setup", None, true, &[], origin.path(), worktree.path()).unwrap();
+        execute_copy_hook(
+            "bin",
+            Some("tools"),
+            true,
+            &[],
+            origin.path(),
+            worktree.path(),
+        )
+        .unwrap();
+
+        let mode = |path: &str| {
+            let metadata = fs::metadata(worktree.path().join(path)).unwrap();
+            metadata.permissions().mode() & 0o777
+        };
+        assert_eq!(mode("bin/setup"), 0o751);
+        assert_eq!(mode("tools/setup"), 0o751);
+        assert_eq!(mode("tools/secret"), 0o600);
+    }
+
     #[test]
     fn test_copy_hook_with_rename() {
         let origin = TempDir::new().unwrap();
@@ -109,6 +500,7 @@ mod tests {
             ".env.local",
             Some(".env"),
             false,
+            &[],
             origin.path(),
             worktree.path(),
         )
@@ -118,13 +510,164 @@ mod tests {
         assert!(dest_file.exists());
     }
 
+    #[test]
+    fn test_copy_dest_name() {
+        assert_eq!(copy_dest_name(".env", None), ".env");
+        assert_eq!(
+            copy_dest_name(".env.local", Some("renamed.env")),
+            "renamed.env"
+        );
+        assert_eq!(
+            copy_dest_name("secrets/.env", Some("config/")),
+            "config/.env"
+        );
+        assert_eq!(copy_dest_name("config", Some("shared/")), "shared/config");
+    }
+
+    #[test]
+    fn test_copy_hook_into_directory() {
+        let origin = TempDir::new().unwrap();
+        let worktree = TempDir::new().unwrap();
+        fs::write(origin.path().join(".env"), "TEST=value").unwrap();
+
+        execute_copy_hook(
+            ".env",
+            Some("config/"),
+            true,
+            &[],
+            origin.path(),
+            worktree.path(),
+        )
+        .unwrap();
+
+        let copied = worktree.path().join("config").join(".env");
+        assert_eq!(fs::read_to_string(copied).unwrap(), "TEST=value");
+    }
+
+    #[test]
+    fn test_copy_hook_directory_with_exclude() {
+        let origin = TempDir::new().unwrap();
+        let worktree = TempDir::new().unwrap();
+        let config_dir = origin.path().join("config");
+        fs::create_dir_all(config_dir.join("local")).unwrap();
+        fs::write(config_dir.join("app.yml"), "app").unwrap();
+        fs::write(config_dir.join("secrets.yml"), "secret").unwrap();
+        fs::write(config_dir.join("local").join("db.yml"), "db").unwrap();
+        fs::write(config_dir.join("local").join("master.key"), "key").unwrap();
+
+        let exclude = vec!["secrets.*".to_string(), "*.key".to_string()];
+        execute_copy_hook(
+            "config",
+            None,
+            true,
+            &exclude,
+            origin.path(),
+            worktree.path(),
+        )
+        .unwrap();
+
+        let copied = worktree.path().join("config");
+        assert!(copied.join("app.yml").exists());
+        assert!(copied.join("local").join("db.yml").exists());
+        assert!(!copied.join("secrets.yml").exists());
+        assert!(!copied.join("local").join("master.key").exists());
+    }
+
+    #[test]
+    fn test_is_excluded() {
+        let exclude = vec!["local/*".to_string(), ".env".to_string()];
+        assert!(is_excluded(Path::new("local/db.yml"), &exclude));
+        assert!(is_excluded(Path::new("nested/.env"), &exclude));
+        assert!(!is_excluded(Path::new("app.yml"), &exclude));
+        assert!(!is_excluded(Path::new("app.yml"), &[]));
+    }
+
+    #[test]
+    fn test_copy_ignored_entries() {
+        let origin = TempDir::new().unwrap();
+        let worktree = TempDir::new().unwrap();
+        fs::write(origin.path().join(".env"), "TOKEN=1").unwrap();
+        fs::create_dir_all(origin.path().join("config/local")).unwrap();
+        fs::write(origin.path().join("config/local/db.yml"), "db").unwrap();
+        fs::write(origin.path().join("config/local/cache.log"), "log").unwrap();
+        fs::create_dir_all(origin.path().join("node_modules/pkg")).unwrap();
+
+        let entries = vec![
+            ".env".to_string(),
+            "config/local/".to_string(),
+            "node_modules/".to_string(),
+        ];
+        let exclude = vec!["node_modules".to_string(), "*.log".to_string()];
+        let copied = copy_ignored_entries(&entries, origin.path(), worktree.path(), &exclude);
+
+        assert_eq!(copied, 2);
+        let dest = worktree.path();
+        assert_eq!(fs::read_to_string(dest.join(".env")).unwrap(), "TOKEN=1");
+        assert_eq!(
+            fs::read_to_string(dest.join("config/local/db.yml")).unwrap(),
+            "db"
+        );
+        assert!(!dest.join("config/local/cache.log").exists());
+        assert!(!dest.join("node_modules").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_copy_ignored_entries_recreates_symlinks() {
+        use std::os::unix::fs::symlink;
+
+        let origin = TempDir::new().unwrap();
+        let worktree = TempDir::new().unwrap();
+        let shared = TempDir::new().unwrap();
+        fs::create_dir_all(origin.path().join("cache/sub")).unwrap();
+        fs::write(shared.path().join("big.bin"), "big").unwrap();
+        // A link back up the tree, and one to a tree outside it
+        symlink("..", origin.path().join("cache/sub/up")).unwrap();
+        symlink(shared.path(), origin.path().join("cache/shared")).unwrap();
+        symlink(shared.path(), origin.path().join("linked")).unwrap();
+
+        let entries = vec!["cache/".to_string(), "linked".to_string()];
+        let copied = copy_ignored_entries(&entries, origin.path(), worktree.path(), &[]);
+
+        assert_eq!(copied, 2);
+        let dest = worktree.path();
+        assert_eq!(
+            fs::read_link(dest.join("cache/sub/up")).unwrap(),
+            Path::new("..")
+        );
+        for link in ["cache/shared", "linked"] {
+            assert_eq!(fs::read_link(dest.join(link)).unwrap(), shared.path());
+        }
+    }
+
+    #[test]
+    fn test_copy_ignored_entries_skips_enclosing_worktree_dir() {
+        let origin = TempDir::new().unwrap();
+        let worktree = origin.path().join(".worktrees").join("feature");
+        fs::create_dir_all(&worktree).unwrap();
+
+        let entries = vec![".worktrees/".to_string()];
+        assert_eq!(
+            copy_ignored_entries(&entries, origin.path(), &worktree, &[]),
+            0
+        );
+    }
+
     #[test]
     fn test_copy_hook_missing_optional() {
         let origin = TempDir::new().unwrap();
         let worktree = TempDir::new().unwrap();
 
         // Should not fail for optional missing file
-        execute_copy_hook(".nonexistent", None, false, origin.path(), worktree.path()).unwrap();
+        execute_copy_hook(
+            ".nonexistent",
+            None,
+            false,
+            &[],
+            origin.path(),
+            worktree.path(),
+        )
+        .unwrap();
     }
 
     #[test]
@@ -133,22 +676,229 @@ mod tests {
         let worktree = TempDir::new().unwrap();
 
         // Should fail for required missing file
-        let result = execute_copy_hook(".nonexistent", None, true, origin.path(), worktree.path());
+        let result = execute_copy_hook(
+            ".nonexistent",
+            None,
+            true,
+            &[],
+            origin.path(),
+            worktree.path(),
+        );
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_after_exit_hooks_run_in_target_dir_without_wrapper() {
+        let target = TempDir::new().unwrap();
+        let hooks = [
+            run_hook("touch ran"),
+            Hook::Copy {
+                from: ".env".to_string(),
+                to: None,
+                required: true,
+                exclude: Vec::new(),
+            },
+        ];
+        let hooks: Vec<&Hook> = hooks.iter().collect();
+
+        execute_after_exit_hooks(&hooks, target.path(), &HashMap::new(), false).unwrap();
+        assert!(target.path().join("ran").exists());
+    }
+
+    #[test]
+    fn test_after_cd_script() {
+        assert_eq!(
+            after_cd_script(&["git fetch", "echo done"], &HashMap::new()),
+            "git fetch\necho done\n"
+        );
+
+        let env = HashMap::from([
+            ("NODE_ENV".to_string(), "development".to_string()),
+            ("GREETING".to_string(), "it's $HOME".to_string()),
+        ]);
+        assert_eq!(
+            after_cd_script(&["npm run warm"], &env),
+            "export GREETING='it'\\''s $HOME'\nexport NODE_ENV='development'\nnpm run warm\n"
+        );
+    }
+
+    #[test]
+    fn test_after_exit_hooks_with_timeout_bypass_wrapper() {
+        let target = TempDir::new().unwrap();
+        let script = target.path().join("after-cd");
+        let hooks = [Hook::Run {
+            command: "touch ran".to_string(),
+            timeout: Some(5),
+        }];
+        let hooks: Vec<&Hook> = hooks.iter().collect();
+
+        std::env::set_var(AFTER_CD_FILE_ENV, &script);
+        let result = execute_after_exit_hooks(&hooks, target.path(), &HashMap::new(), true);
+        std::env::remove_var(AFTER_CD_FILE_ENV);
+        result.unwrap();
+        assert!(target.path().join("ran").exists());
+        assert!(!script.exists());
+    }
+
     #[test]
     fn test_run_hook_success() {
         let worktree = TempDir::new().unwrap();
 
-        execute_run_hook("true", worktree.path()).unwrap();
+        execute_run_hook("true", None, worktree.path(), &HashMap::new()).unwrap();
     }
 
     #[test]
     fn test_run_hook_failure() {
         let worktree = TempDir::new().unwrap();
 
-        let result = execute_run_hook("false", worktree.path());
+        let result = execute_run_hook("false", None, worktree.path(), &HashMap::new());
         assert!(result.is_err());
     }
+
+    fn run_hook(command: &str) -> Hook {
+        Hook::Run {
+            command: command.to_string(),
+            timeout: None,
+        }
+    }
+
+    #[test]
+    fn test_execute_hooks_serial_stops_at_first_failure() {
+        let origin = TempDir::new().unwrap();
+        let worktree = TempDir::new().unwrap();
+        let hooks = [run_hook("false"), run_hook("touch ran")];
+        let hooks: Vec<&Hook> = hooks.iter().collect();
+
+        assert!(execute_hooks(
+            &hooks,
+            origin.path(),
+            worktree.path(),
+            &HashMap::new(),
+            false
+        )
+        .is_err());
+        assert!(!worktree.path().join("ran").exists());
+    }
+
+    #[test]
+    fn test_execute_hooks_parallel_runs_all_and_reports_failures() {
+        let origin = TempDir::new().unwrap();
+        let worktree = TempDir::new().unwrap();
+        fs::write(origin.path().join(".env"), "TEST=value").unwrap();
+        let hooks = [
+            run_hook("exit 1"),
+            Hook::Copy {
+                from: ".env".to_string(),
+                to: None,
+                required: true,
+                exclude: Vec::new(),
+            },
+            run_hook("touch ran"),
+            run_hook("exit 2"),
+        ];
+        let hooks: Vec<&Hook> = hooks.iter().collect();
+
+        let err = execute_hooks(
+            &hooks,
+            origin.path(),
+            worktree.path(),
+            &HashMap::new(),
+            true,
+        )
+        .unwrap_err();
+        let message = err.to_string();
+        assert!(message.starts_with("2 hooks failed:"), "{}", message);
+        assert!(message.contains("exit 1") && message.contains("exit 2"));
+        assert!(worktree.path().join(".env").exists());
+        assert!(worktree.path().join("ran").exists());
+    }
+
+    #[test]
+    fn test_execute_hooks_parallel_empty() {
+        let dir = TempDir::new().unwrap();
+        execute_hooks(&[], dir.path(), dir.path(), &HashMap::new(), true).unwrap();
+    }
+
+    #[test]
+    fn test_run_hook_in_path_with_spaces() {
+        let temp_dir = TempDir::new().unwrap();
+        let worktree = temp_dir.path().join("my worktree");
+        fs::create_dir(&worktree).unwrap();
+
+        execute_run_hook("pwd > cwd.txt", None, &worktree, &HashMap::new()).unwrap();
+
+        let cwd = fs::read_to_string(worktree.join("cwd.txt")).unwrap();
+        assert_eq!(cwd.trim_end(), worktree.to_string_lossy());
+    }
+
+    #[test]
+    fn test_run_hook_gets_hook_env() {
+        let worktree = TempDir::new().unwrap();
+        let env = HashMap::from([("NODE_ENV".to_string(), "development".to_string())]);
+
+        execute_run_hook("echo $NODE_ENV > env.txt", None, worktree.path(), &env).unwrap();
+
+        let value = fs::read_to_string(worktree.path().join("env.txt")).unwrap();
+        assert_eq!(value, "development\n");
+    }
+
+    #[test]
+    fn test_run_hook_within_timeout() {
+        let worktree = TempDir::new().unwrap();
+
+        execute_run_hook(
+            "true",
+            Some(Duration::from_secs(5)),
+            worktree.path(),
+            &HashMap::new(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_run_hook_timeout() {
+        let worktree = TempDir::new().unwrap();
+
+        let started = Instant::now();
+        let result = execute_run_hook(
+            "sleep 5",
+            Some(Duration::ZERO),
+            worktree.path(),
+            &HashMap::new(),
+        );
+
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("timed out"), "unexpected error: {}", err);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_hook_timeout_kills_started_processes() {
+        let worktree = TempDir::new().unwrap();
+
+        let result = execute_run_hook(
+            "sleep 30 & echo $! > pid; wait",
+            Some(Duration::from_millis(500)),
+            worktree.path(),
+            &HashMap::new(),
+        );
+        assert!(result.unwrap_err().to_string().contains("timed out"));
+
+        // Gone, or a zombie until whoever inherited it reaps it
+        let pid = fs::read_to_string(worktree.path().join("pid")).unwrap();
+        let alive = || {
+            let output = Command::new("ps")
+                .args(["-o", "stat=", "-p", pid.trim()])
+                .output()
+                .unwrap();
+            let stat = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            !stat.is_empty() && !stat.starts_with('Z')
+        };
+        let started = Instant::now();
+        while alive() && started.elapsed() < Duration::from_secs(5) {
+            thread::sleep(POLL_INTERVAL);
+        }
+        assert!(!alive(), "sleep {} survived the timeout", pid.trim());
+    }
 }
@@ -1,5 +1,10 @@
 pub mod cmd;
 pub mod config;
+pub mod debug_log;
+pub mod error;
 pub mod git;
+pub mod glob;
+pub mod history;
 pub mod hooks;
+pub mod shell;
 pub mod state;
@@ -1,10 +1,18 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Instant;
 
 mod cmd;
 mod config;
+mod debug_log;
+mod error;
 mod git;
+mod glob;
+mod history;
 mod hooks;
+mod shell;
 mod state;
 
 #[derive(Parser)]
@@ -12,6 +20,9 @@ mod state;
 #[command(about = "A CLI tool for managing temporary git worktree environments")]
 #[command(version)]
 struct Cli {
+    /// Print each git, gh and hook command to stderr before running it
+    #[arg(long, short, global = true)]
+    verbose: bool,
     #[command(subcommand)]
     command: Commands,
 }
@@ -20,47 +31,141 @@ struct Cli {
 enum Commands {
     /// Create a worktree for reviewing a GitHub PR
     Pr {
-        /// PR number
-        number: u32,
+        #[command(flatten)]
+        args: cmd::pr::PrArgs,
     },
 
     /// Create a new worktree for feature development
     New {
-        /// Branch suffix (prompted interactively if not provided)
-        #[arg(conflicts_with = "random_suffix")]
-        branch_suffix: Option<String>,
-        /// Generate a random branch suffix automatically
-        #[arg(long)]
-        random_suffix: bool,
+        #[command(flatten)]
+        args: cmd::new::NewArgs,
     },
 
     /// Create a worktree from a remote branch
     #[command(visible_alias = "co")]
     Checkout {
-        /// Remote branch name (e.g., main, feature/foo, or origin/main)
-        remote_branch: String,
+        /// Remote branch names (e.g., main, feature/foo, or origin/main); with several,
+        /// a worktree is created for each and the shell stays where it is
+        #[arg(required = true, value_name = "REMOTE_BRANCH")]
+        remote_branches: Vec<String>,
+        /// Don't fetch; use the existing `origin/<branch>` ref
+        #[arg(long)]
+        offline: bool,
+        /// If origin has no such branch, use the local branch or create it from the default branch
+        #[arg(long)]
+        create: bool,
+        #[command(flatten)]
+        options: cmd::CreateOptions,
     },
 
     /// List all managed worktrees
     #[command(visible_alias = "ls")]
-    List,
+    List {
+        #[command(flatten)]
+        args: cmd::list::ListArgs,
+    },
+
+    /// Show branch, upstream and changes of the current worktree
+    Status {
+        /// Show every managed worktree (runs git in each; slower)
+        #[arg(long)]
+        all: bool,
+    },
 
     /// Change to a worktree directory
     Cd {
         /// Worktree name or '@' for origin repository
         target: Option<String>,
+        /// Print `cd '<path>'` for `eval "$(gj cd <name> --print-cmd)"` instead of the path
+        #[arg(long)]
+        print_cmd: bool,
     },
 
     /// Clean up the current worktree and return to origin repository
     Exit {
-        /// Force removal even with uncommitted changes
-        #[arg(long, short)]
-        force: bool,
-        /// Merge the worktree branch into the default branch before exiting
+        #[command(flatten)]
+        args: cmd::exit::ExitArgs,
+    },
+
+    /// Pin a worktree so pruning skips it
+    Pin {
+        /// Worktree name (defaults to the current worktree)
+        name: Option<String>,
+    },
+
+    /// Unpin a worktree pinned with `gj pin`
+    Unpin {
+        /// Worktree name (defaults to the current worktree)
+        name: Option<String>,
+    },
+
+    /// Add labels to a worktree (e.g. wip, review) for filtering with `gj list --label`
+    Label {
+        /// Worktree name
+        name: String,
+        /// Labels to add
+        #[arg(required = true)]
+        labels: Vec<String>,
+        /// Remove the labels instead
         #[arg(long, short)]
-        merge: bool,
+        remove: bool,
     },
 
+    /// Point worktree states at a moved origin repository (run inside it, or give both paths)
+    Relink {
+        /// Previous location of the repository
+        #[arg(requires = "new")]
+        old: Option<PathBuf>,
+        /// New location of the repository
+        new: Option<PathBuf>,
+        /// Show what would be relinked without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Archive a worktree as .tar.gz (excluding .git)
+    Archive {
+        /// Worktree name (defaults to the current worktree)
+        name: Option<String>,
+        /// Only include files not ignored by .gitignore
+        #[arg(long)]
+        respect_gitignore: bool,
+    },
+
+    /// Manage the gj configuration file
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
+    /// Print the worktree base directory for the current repository (or --repo)
+    #[command(name = "base-dir")]
+    BaseDir {
+        /// Registered repository name instead of the current repository
+        #[arg(long)]
+        repo: Option<String>,
+    },
+
+    /// List the hooks gj would run for the current repository (or --repo), and where each is set
+    Hooks {
+        /// Registered repository name instead of the current repository
+        #[arg(long)]
+        repo: Option<String>,
+    },
+
+    /// Summarize your gj usage from the local activity log (~/.gj/history.jsonl)
+    Stats,
+
+    /// Diagnose the gj environment (git, gh, config, state)
+    Doctor {
+        /// Output results as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Print the gj version (with --verbose: build commit, rustc, and git/gh versions)
+    Version,
+
     /// Output shell initialization script
     #[command(name = "shell-init")]
     ShellInit {
@@ -73,20 +178,103 @@ enum Commands {
         /// Overwrite existing configuration file
         #[arg(long, short)]
         force: bool,
+
+        /// Append commented-out examples of every hook type
+        #[arg(long)]
+        example: bool,
     },
+
+    /// Print open PRs as `number<TAB>title` lines for shell completion
+    #[command(name = "__complete_prs", hide = true)]
+    CompletePrs,
 }
 
-fn main() -> Result<()> {
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Set a configuration value (e.g., default.prefix, repos.<name>.base_dir)
+    Set {
+        /// Dotted configuration key
+        key: String,
+        /// Value to set
+        value: String,
+        /// Rewrite the config even if comments would be lost
+        #[arg(long, short)]
+        force: bool,
+    },
+    /// Open the configuration file in your editor (creating it if missing)
+    Edit,
+}
+
+fn main() -> ExitCode {
     let cli = Cli::parse();
+    let started = Instant::now();
+    debug_log::start();
+
+    let result = run(cli);
+    debug_log::finish(&result, started);
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        // A cancelled prompt is not an error; exit like an interrupted shell command
+        Err(e) if error::is_cancelled(&e) => ExitCode::from(130),
+        Err(e) => {
+            error::report(&e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
+    git::set_verbose(cli.verbose);
+
+    let config = config::Config::load().ok();
+    if let Some(git_path) = config.as_ref().and_then(|config| config.get_git_path()) {
+        git::set_git_path(git_path);
+    }
+    // Not while a shell starts up or completes, where a notice would only get in the way
+    let quiet = matches!(
+        cli.command,
+        Commands::ShellInit { .. } | Commands::CompletePrs | Commands::Init { .. }
+    );
+    if let Some(config) = config.as_ref().filter(|_| !quiet) {
+        state::warn_stale(config);
+    }
 
     match cli.command {
-        Commands::Pr { number } => cmd::pr::run(number),
-        Commands::New { branch_suffix, random_suffix } => cmd::new::run(branch_suffix, random_suffix),
-        Commands::Checkout { remote_branch } => cmd::checkout::run(remote_branch),
-        Commands::List => cmd::list::run(),
-        Commands::Cd { target } => cmd::cd::run(target),
-        Commands::Exit { force, merge } => cmd::exit::run(force, merge),
+        Commands::Pr { args } => cmd::pr::run(args),
+        Commands::New { args } => cmd::new::run(args),
+        Commands::Checkout {
+            remote_branches,
+            offline,
+            create,
+            options,
+        } => cmd::checkout::run(remote_branches, offline, create, options),
+        Commands::List { args } => cmd::list::run(args),
+        Commands::Status { all } => cmd::status::run(all),
+        Commands::Cd { target, print_cmd } => cmd::cd::run(target, print_cmd),
+        Commands::Exit { args } => cmd::exit::run(args),
+        Commands::Pin { name } => cmd::pin::run(name, true),
+        Commands::Unpin { name } => cmd::pin::run(name, false),
+        Commands::Label {
+            name,
+            labels,
+            remove,
+        } => cmd::label::run(&name, &labels, remove),
+        Commands::Relink { old, new, dry_run } => cmd::relink::run(old, new, dry_run),
+        Commands::Archive {
+            name,
+            respect_gitignore,
+        } => cmd::archive::run(name, respect_gitignore),
+        Commands::Config { action } => match action {
+            ConfigAction::Set { key, value, force } => cmd::config::set(&key, &value, force),
+            ConfigAction::Edit => cmd::config::edit(),
+        },
+        Commands::BaseDir { repo } => cmd::base_dir::run(repo),
+        Commands::Hooks { repo } => cmd::hook_plan::run(repo),
+        Commands::Stats => cmd::stats::run(),
+        Commands::Doctor { json } => cmd::doctor::run(json),
+        Commands::Version => cmd::version::run(cli.verbose),
         Commands::ShellInit { shell } => cmd::shell_init::run(&shell),
-        Commands::Init { force } => cmd::init::run(force),
+        Commands::Init { force, example } => cmd::init::run(force, example),
+        Commands::CompletePrs => cmd::pr::complete(),
     }
 }
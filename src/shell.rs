@@ -0,0 +1,42 @@
+/// Quote a word so that sh takes it literally, always wrapping it in single quotes
+pub(crate) fn single_quote(word: &str) -> String {
+    format!("'{}'", word.replace('\'', "'\\''"))
+}
+
+/// Quote a word for display in a shell command line, leaving plain words bare
+pub(crate) fn quote_for_display(word: &str) -> String {
+    let plain = !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_alphanumeric() || "-_./=:,+@%^{}~".contains(c));
+    if plain {
+        word.to_string()
+    } else {
+        single_quote(word)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_quote() {
+        assert_eq!(single_quote("main"), "'main'");
+        assert_eq!(single_quote(""), "''");
+        assert_eq!(single_quote("it's"), "'it'\\''s'");
+        assert_eq!(single_quote("$HOME `x`"), "'$HOME `x`'");
+    }
+
+    #[test]
+    fn test_quote_for_display() {
+        assert_eq!(quote_for_display("worktree"), "worktree");
+        assert_eq!(
+            quote_for_display("refs/heads/feature-1"),
+            "refs/heads/feature-1"
+        );
+        assert_eq!(quote_for_display(""), "''");
+        assert_eq!(quote_for_display("my repo"), "'my repo'");
+        assert_eq!(quote_for_display("it's"), "'it'\\''s'");
+    }
+}
@@ -5,9 +5,21 @@ use sha2::{Digest, Sha256};
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// Current version of the state file format
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Directory inside each worktree holding its state marker
+pub const MARKER_DIR: &str = ".gj";
+
+/// Name of the state marker file within `MARKER_DIR`
+const MARKER_FILE: &str = "worktree.json";
+
 /// State information for a managed worktree
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct WorktreeState {
+    /// State file format version (0 for legacy files written without one)
+    #[serde(default)]
+    pub schema_version: u32,
     /// Absolute path to the worktree
     pub worktree_path: PathBuf,
     /// Absolute path to the origin repository
@@ -16,20 +28,33 @@ pub struct WorktreeState {
     pub branch: String,
     /// When the worktree was created
     pub created_at: DateTime<Utc>,
+    /// Pull request number for worktrees created by `gj pr`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pr_number: Option<u32>,
+    /// Pinned worktrees are skipped by pruning (see `gj pin`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+    /// Labels for organizing worktrees (see `gj label`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
 }
 
 impl WorktreeState {
     /// Create a new worktree state
     pub fn new(worktree_path: PathBuf, origin_repo: PathBuf, branch: String) -> Self {
         WorktreeState {
+            schema_version: SCHEMA_VERSION,
             worktree_path,
             origin_repo,
             branch,
             created_at: Utc::now(),
+            pr_number: None,
+            pinned: false,
+            labels: Vec::new(),
         }
     }
 
-    /// Save the state to a file
+    /// Save the state to the central state file and the worktree's marker
     pub fn save(&self) -> Result<()> {
         let state_file = state_file_path(&self.worktree_path)?;
 
@@ -43,27 +68,60 @@ impl WorktreeState {
         let content =
             serde_json::to_string_pretty(self).context("Failed to serialize worktree state")?;
 
-        fs::write(&state_file, content)
+        fs::write(&state_file, &content)
             .with_context(|| format!("Failed to write state file: {}", state_file.display()))?;
 
+        if self.worktree_path.is_dir() {
+            write_marker(&self.worktree_path, &content)?;
+        }
+
         Ok(())
     }
 
-    /// Load state from a worktree path
+    /// Load state from a worktree path, preferring the marker inside the worktree.
+    /// A marker recorded at another path means the worktree was moved; its state is relocated.
     pub fn load(worktree_path: &Path) -> Result<Option<Self>> {
+        let marker = marker_path(worktree_path);
+        if marker.exists() {
+            let mut state = read_state_file(&marker)?;
+            if state.worktree_path != worktree_path && !state.worktree_path.exists() {
+                state.relocate(worktree_path)?;
+            }
+            return Ok(Some(state));
+        }
+
         let state_file = state_file_path(worktree_path)?;
 
         if !state_file.exists() {
             return Ok(None);
         }
 
-        let content = fs::read_to_string(&state_file)
-            .with_context(|| format!("Failed to read state file: {}", state_file.display()))?;
+        read_state_file(&state_file).map(Some)
+    }
 
-        let state: WorktreeState = serde_json::from_str(&content)
-            .with_context(|| format!("Failed to parse state file: {}", state_file.display()))?;
+    /// Point the state at the worktree's new location, replacing the old central file
+    fn relocate(&mut self, worktree_path: &Path) -> Result<()> {
+        let old_file = state_file_path(&self.worktree_path)?;
+        if old_file.exists() {
+            fs::remove_file(&old_file).with_context(|| {
+                format!("Failed to delete state file: {}", old_file.display())
+            })?;
+        }
+
+        self.worktree_path = worktree_path.to_path_buf();
+        self.save()
+    }
 
-        Ok(Some(state))
+    /// Upgrade state written by an older gj to the current schema.
+    /// Returns whether anything changed; newer versions are left untouched.
+    fn migrate(&mut self) -> bool {
+        if self.schema_version >= SCHEMA_VERSION {
+            return false;
+        }
+
+        // 0 -> 1: legacy files had no version; all fields are unchanged
+        self.schema_version = SCHEMA_VERSION;
+        true
     }
 
     /// Load state from the current directory
@@ -72,14 +130,17 @@ impl WorktreeState {
         Self::load(&current_dir)
     }
 
-    /// Delete the state file
+    /// Delete the state file and the worktree's marker
     pub fn delete(&self) -> Result<()> {
         let state_file = state_file_path(&self.worktree_path)?;
+        let marker = marker_path(&self.worktree_path);
 
-        if state_file.exists() {
-            fs::remove_file(&state_file).with_context(|| {
-                format!("Failed to delete state file: {}", state_file.display())
-            })?;
+        for file in [&state_file, &marker] {
+            if file.exists() {
+                fs::remove_file(file).with_context(|| {
+                    format!("Failed to delete state file: {}", file.display())
+                })?;
+            }
         }
 
         Ok(())
@@ -123,6 +184,40 @@ fn state_file_path(worktree_path: &Path) -> Result<PathBuf> {
     Ok(state_dir.join(format!("{}.json", hash)))
 }
 
+/// Get the marker file path inside a worktree (`.gj/worktree.json`)
+fn marker_path(worktree_path: &Path) -> PathBuf {
+    worktree_path.join(MARKER_DIR).join(MARKER_FILE)
+}
+
+/// Write the marker, with a `.gitignore` so git never reports the directory as untracked
+fn write_marker(worktree_path: &Path, content: &str) -> Result<()> {
+    let marker = marker_path(worktree_path);
+    let dir = worktree_path.join(MARKER_DIR);
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create marker directory: {}", dir.display()))?;
+    fs::write(dir.join(".gitignore"), "*\n")
+        .with_context(|| format!("Failed to write {}", dir.join(".gitignore").display()))?;
+    fs::write(&marker, content)
+        .with_context(|| format!("Failed to write state marker: {}", marker.display()))
+}
+
+/// Read a state file, migrating and rewriting it if it uses an older schema
+fn read_state_file(state_file: &Path) -> Result<WorktreeState> {
+    let content = fs::read_to_string(state_file)
+        .with_context(|| format!("Failed to read state file: {}", state_file.display()))?;
+
+    let mut state: WorktreeState = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse state file: {}", state_file.display()))?;
+
+    if state.migrate() {
+        if let Err(e) = state.save() {
+            eprintln!("Warning: Failed to upgrade state file: {}", e);
+        }
+    }
+
+    Ok(state)
+}
+
 /// List all worktree states
 pub fn list_all_states() -> Result<Vec<WorktreeState>> {
     let state_dir = state_dir()?;
@@ -140,20 +235,99 @@ pub fn list_all_states() -> Result<Vec<WorktreeState>> {
         let path = entry.path();
 
         if path.extension().map(|e| e == "json").unwrap_or(false) {
-            if let Ok(content) = fs::read_to_string(&path) {
-                if let Ok(state) = serde_json::from_str::<WorktreeState>(&content) {
-                    states.push(state);
-                }
+            if let Ok(state) = read_state_file(&path) {
+                states.push(state);
             }
         }
     }
 
     // Sort by creation time, newest first
-    states.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    states.sort_by_key(|s| std::cmp::Reverse(s.created_at));
 
     Ok(states)
 }
 
+/// Delete state files whose worktree no longer exists, returning the removed states.
+/// Only states for which `in_scope` holds are considered; pinned worktrees are kept.
+pub fn prune_stale_states(in_scope: impl Fn(&WorktreeState) -> bool) -> Result<Vec<WorktreeState>> {
+    let mut pruned = Vec::new();
+
+    for state in list_all_states()? {
+        if in_scope(&state) && !state.pinned && !state.worktree_path.exists() {
+            state.delete()?;
+            pruned.push(state);
+        }
+    }
+
+    Ok(pruned)
+}
+
+/// File in the state directory recording when the stale worktree notice was last shown
+const STALE_NOTICE_FILE: &str = "stale_notice";
+
+/// Unpinned worktrees that still exist and were created more than `threshold` before `now`
+fn older_than(
+    states: Vec<WorktreeState>,
+    threshold: chrono::Duration,
+    now: DateTime<Utc>,
+) -> Vec<WorktreeState> {
+    states
+        .into_iter()
+        .filter(|s| !s.pinned && now - s.created_at > threshold && s.worktree_path.exists())
+        .collect()
+}
+
+/// Whether the stale worktree notice is due again, given when it was last shown
+fn stale_notice_due(last_shown: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+    last_shown.is_none_or(|last| now - last >= chrono::Duration::days(1))
+}
+
+/// Note worktrees older than `default.warn_stale_after` on stderr, at most once a day.
+/// The day's check is recorded whatever it finds, including an invalid setting, so that
+/// other commands skip both the warning and the scan of every state file.
+pub fn warn_stale(config: &crate::config::Config) {
+    if matches!(config.default.warn_stale_after.as_deref(), None | Some("off")) {
+        return;
+    }
+    let Ok(notice_file) = state_dir().map(|dir| dir.join(STALE_NOTICE_FILE)) else {
+        return;
+    };
+
+    let now = Utc::now();
+    let last_shown = fs::read_to_string(&notice_file)
+        .ok()
+        .and_then(|content| DateTime::parse_from_rfc3339(content.trim()).ok())
+        .map(|at| at.with_timezone(&Utc));
+    if !stale_notice_due(last_shown, now) {
+        return;
+    }
+    // Throttling is best effort: failing to record it only means the check comes back sooner
+    let _ = fs::write(&notice_file, now.to_rfc3339());
+
+    let threshold = match config.get_warn_stale_after() {
+        Ok(Some(threshold)) => threshold,
+        Ok(None) => return,
+        Err(e) => {
+            eprintln!("Warning: {:#}", e);
+            return;
+        }
+    };
+    let count = match list_all_states() {
+        Ok(states) => older_than(states, threshold, now).len(),
+        Err(_) => return,
+    };
+    if count == 0 {
+        return;
+    }
+
+    let age = config.default.warn_stale_after.as_deref().unwrap_or_default();
+    let noun = if count == 1 { "worktree" } else { "worktrees" };
+    eprintln!(
+        "You have {} {} older than {} — run `gj list --age-threshold {}` to review them.",
+        count, noun, age, age
+    );
+}
+
 // Hex encoding helper (to avoid another dependency)
 mod hex {
     pub fn encode(bytes: &[u8]) -> String {
@@ -257,6 +431,216 @@ mod tests {
         std::env::remove_var("GJ_STATE_DIR");
     }
 
+    #[test]
+    fn test_state_marker_follows_moved_worktree() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let old_path = temp_dir.path().join("old");
+        let new_path = temp_dir.path().join("new");
+        fs::create_dir(&old_path).unwrap();
+
+        let state_root = temp_dir.path().join("state");
+        std::env::set_var("GJ_STATE_DIR", &state_root);
+
+        let state = WorktreeState::new(
+            old_path.clone(),
+            PathBuf::from("/origin"),
+            "moved-branch".to_string(),
+        );
+        state.save().unwrap();
+        assert!(marker_path(&old_path).exists());
+        assert_eq!(fs::read_to_string(old_path.join(".gj/.gitignore")).unwrap(), "*\n");
+
+        // Simulate `git worktree move`
+        fs::rename(&old_path, &new_path).unwrap();
+
+        let loaded = WorktreeState::load(&new_path).unwrap().unwrap();
+        assert_eq!(loaded.worktree_path, new_path);
+        assert_eq!(loaded.branch, "moved-branch");
+
+        // The central state was relocated along with it
+        let states = list_all_states().unwrap();
+        assert_eq!(states.len(), 1);
+        assert_eq!(states[0].worktree_path, new_path);
+
+        loaded.delete().unwrap();
+        assert!(!marker_path(&new_path).exists());
+        assert!(WorktreeState::load(&new_path).unwrap().is_none());
+
+        std::env::remove_var("GJ_STATE_DIR");
+    }
+
+    #[test]
+    fn test_prune_stale_states() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let temp_dir = TempDir::new().unwrap();
+
+        let state_root = temp_dir.path().join("state");
+        std::env::set_var("GJ_STATE_DIR", &state_root);
+
+        let existing_path = temp_dir.path().join("existing");
+        fs::create_dir(&existing_path).unwrap();
+        let existing = WorktreeState::new(
+            existing_path.clone(),
+            PathBuf::from("/origin"),
+            "existing".to_string(),
+        );
+        existing.save().unwrap();
+
+        let missing_path = temp_dir.path().join("missing");
+        let missing = WorktreeState::new(
+            missing_path.clone(),
+            PathBuf::from("/origin"),
+            "missing".to_string(),
+        );
+        missing.save().unwrap();
+
+        let other_repo_path = temp_dir.path().join("other-repo");
+        let other_repo = WorktreeState::new(
+            other_repo_path.clone(),
+            PathBuf::from("/other"),
+            "other-repo".to_string(),
+        );
+        other_repo.save().unwrap();
+
+        let pinned_path = temp_dir.path().join("pinned");
+        let mut pinned = WorktreeState::new(
+            pinned_path.clone(),
+            PathBuf::from("/origin"),
+            "pinned".to_string(),
+        );
+        pinned.pinned = true;
+        pinned.save().unwrap();
+
+        let pruned = prune_stale_states(|s| s.origin_repo == Path::new("/origin")).unwrap();
+        assert_eq!(pruned.len(), 1);
+        assert_eq!(pruned[0].worktree_path, missing_path);
+        assert!(WorktreeState::load(&pinned_path).unwrap().unwrap().pinned);
+
+        assert!(WorktreeState::load(&existing_path).unwrap().is_some());
+        assert!(WorktreeState::load(&missing_path).unwrap().is_none());
+        assert!(WorktreeState::load(&other_repo_path).unwrap().is_some());
+
+        std::env::remove_var("GJ_STATE_DIR");
+    }
+
+    #[test]
+    fn test_older_than() {
+        let temp_dir = TempDir::new().unwrap();
+        let now = Utc::now();
+        let state = |name: &str, age_days: i64, pinned: bool| {
+            let path = temp_dir.path().join(name);
+            fs::create_dir_all(&path).unwrap();
+            let mut state = WorktreeState::new(path, PathBuf::from("/origin"), name.to_string());
+            state.created_at = now - chrono::Duration::days(age_days);
+            state.pinned = pinned;
+            state
+        };
+        let mut missing = state("missing", 30, false);
+        missing.worktree_path = temp_dir.path().join("gone");
+        let states = vec![
+            state("old", 30, false),
+            state("new", 3, false),
+            state("pinned", 30, true),
+            missing,
+        ];
+
+        let old = older_than(states, chrono::Duration::days(14), now);
+        assert_eq!(old.len(), 1);
+        assert_eq!(old[0].branch, "old");
+    }
+
+    #[test]
+    fn test_stale_notice_due() {
+        let now = Utc::now();
+        assert!(stale_notice_due(None, now));
+        assert!(!stale_notice_due(Some(now - chrono::Duration::hours(23)), now));
+        assert!(stale_notice_due(Some(now - chrono::Duration::hours(24)), now));
+    }
+
+    #[test]
+    fn test_warn_stale_records_the_check_for_an_invalid_setting() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        std::env::set_var("GJ_STATE_DIR", temp_dir.path());
+
+        let config: crate::config::Config =
+            toml::from_str("[default]\nwarn_stale_after = \"99999999999999d\"\n").unwrap();
+        warn_stale(&config);
+
+        // Other commands today skip the warning and the scan
+        let notice = fs::read_to_string(temp_dir.path().join(STALE_NOTICE_FILE)).unwrap();
+        let shown = DateTime::parse_from_rfc3339(notice.trim()).unwrap();
+        assert!(!stale_notice_due(Some(shown.with_timezone(&Utc)), Utc::now()));
+
+        std::env::remove_var("GJ_STATE_DIR");
+    }
+
+    #[test]
+    fn test_load_migrates_legacy_state() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let worktree_path = temp_dir.path().join("worktree");
+
+        let state_root = temp_dir.path().join("state");
+        std::env::set_var("GJ_STATE_DIR", &state_root);
+
+        // State file written before schema_version existed
+        let legacy = serde_json::json!({
+            "worktree_path": worktree_path,
+            "origin_repo": "/origin",
+            "branch": "legacy-branch",
+            "created_at": "2026-01-01T00:00:00Z",
+        });
+        let state_file = state_file_path(&worktree_path).unwrap();
+        fs::create_dir_all(&state_root).unwrap();
+        fs::write(&state_file, legacy.to_string()).unwrap();
+
+        let loaded = WorktreeState::load(&worktree_path).unwrap().unwrap();
+        assert_eq!(loaded.schema_version, SCHEMA_VERSION);
+        assert_eq!(loaded.branch, "legacy-branch");
+
+        // The file has been rewritten with the current version
+        let content = fs::read_to_string(&state_file).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(value["schema_version"], SCHEMA_VERSION);
+
+        std::env::remove_var("GJ_STATE_DIR");
+    }
+
+    #[test]
+    fn test_pr_number_serialization() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let mut state = WorktreeState::new(
+            PathBuf::from("/worktree"),
+            PathBuf::from("/origin"),
+            "branch".to_string(),
+        );
+        let json = serde_json::to_value(&state).unwrap();
+        assert!(json.get("pr_number").is_none());
+        assert!(json.get("labels").is_none());
+
+        state.pr_number = Some(42);
+        let json = serde_json::to_string(&state).unwrap();
+        let loaded: WorktreeState = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.pr_number, Some(42));
+    }
+
+    #[test]
+    fn test_migrate_leaves_newer_versions_alone() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let mut state = WorktreeState::new(
+            PathBuf::from("/worktree"),
+            PathBuf::from("/origin"),
+            "branch".to_string(),
+        );
+        assert!(!state.migrate());
+
+        state.schema_version = SCHEMA_VERSION + 1;
+        assert!(!state.migrate());
+        assert_eq!(state.schema_version, SCHEMA_VERSION + 1);
+    }
+
     #[test]
     fn test_display_path_with_home() {
         let _lock = ENV_LOCK.lock().unwrap();
@@ -0,0 +1,693 @@
+//! The shell wrapper from `gj shell-init` cds into whatever gj prints to stdout when it
+//! is a directory. These tests run the built binary to pin that contract: on success
+//! stdout is exactly one path, messages go to stderr, and failures exit nonzero with
+//! nothing on stdout.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use tempfile::TempDir;
+
+/// A sandboxed HOME with a gj config and a git repository with a GitHub origin
+struct Sandbox {
+    _dir: TempDir,
+    home: PathBuf,
+    repo: PathBuf,
+    base_dir: PathBuf,
+}
+
+impl Sandbox {
+    fn new() -> Self {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let home = root.join("home");
+        let repo = root.join("repo");
+        let base_dir = root.join("worktrees");
+
+        fs::create_dir_all(home.join(".gj")).unwrap();
+        fs::write(
+            home.join(".gj").join("config.toml"),
+            format!("[default]\nbase_dir = \"{}\"\n", base_dir.display()),
+        )
+        .unwrap();
+
+        let sandbox = Sandbox {
+            _dir: dir,
+            home,
+            repo,
+            base_dir,
+        };
+
+        fs::create_dir_all(&sandbox.repo).unwrap();
+        fs::write(sandbox.repo.join("README.md"), "hello\n").unwrap();
+        for args in [
+            &["init", "-q", "-b", "main"][..],
+            &["add", "."],
+            &["commit", "-q", "-m", "init"],
+            &["remote", "add", "origin", "git@github.com:me/repo.git"],
+        ] {
+            let output = sandbox.git(args);
+            assert!(output.status.success(), "git {:?}: {:?}", args, output);
+        }
+
+        sandbox
+    }
+
+    /// A command with the sandbox environment, run in `dir`
+    fn command(&self, program: &str, dir: &Path) -> Command {
+        let mut command = Command::new(program);
+        command
+            .current_dir(dir)
+            .env("HOME", &self.home)
+            .env("GIT_AUTHOR_NAME", "gj")
+            .env("GIT_AUTHOR_EMAIL", "gj@example.com")
+            .env("GIT_COMMITTER_NAME", "gj")
+            .env("GIT_COMMITTER_EMAIL", "gj@example.com")
+            .env("GIT_CONFIG_NOSYSTEM", "1")
+            .env("NO_COLOR", "1")
+            .env_remove("GJ_STATE_DIR")
+            .env_remove("GJ_HISTORY_FILE")
+            .env_remove("GJ_AFTER_CD_FILE")
+            .env_remove("GJ_DEFAULT_NAME")
+            .env_remove("GITHUB_TOKEN");
+        command
+    }
+
+    fn git(&self, args: &[&str]) -> Output {
+        self.command("git", &self.repo).args(args).output().unwrap()
+    }
+
+    fn gj(&self, args: &[&str], dir: &Path) -> Output {
+        self.command(env!("CARGO_BIN_EXE_gj"), dir)
+            .args(args)
+            .output()
+            .unwrap()
+    }
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+/// Assert success with stdout exactly one line holding an existing directory; return it
+fn assert_path_output(output: &Output) -> PathBuf {
+    assert!(output.status.success(), "gj failed: {}", stderr(output));
+    let out = stdout(output);
+    let path = out
+        .strip_suffix('\n')
+        .unwrap_or_else(|| panic!("stdout is not one line: {:?}", out));
+    assert!(!path.contains('\n'), "stdout has several lines: {:?}", out);
+    let path = PathBuf::from(path);
+    assert!(path.is_dir(), "stdout is not a directory: {:?}", out);
+    path
+}
+
+/// Assert a nonzero exit with an error on stderr and nothing on stdout
+fn assert_failure(output: &Output) {
+    assert!(!output.status.success(), "gj unexpectedly succeeded");
+    assert_eq!(stdout(output), "", "stdout must be empty on failure");
+    assert!(
+        stderr(output).contains("error:"),
+        "stderr: {}",
+        stderr(output)
+    );
+}
+
+#[test]
+fn test_new_cd_exit_print_only_the_path() {
+    let sandbox = Sandbox::new();
+
+    let output = sandbox.gj(&["new", "feature"], &sandbox.repo);
+    let worktree = assert_path_output(&output);
+    assert!(worktree.starts_with(&sandbox.base_dir));
+    assert!(worktree.to_string_lossy().ends_with("_feature"));
+    assert!(stderr(&output).contains("Created worktree"));
+
+    let name = worktree.file_name().unwrap().to_str().unwrap();
+    let output = sandbox.gj(&["cd", name], &sandbox.repo);
+    assert_eq!(assert_path_output(&output), worktree);
+
+    // Not a directory, so the wrapper prints it instead of changing into it
+    let output = sandbox.gj(&["cd", name, "--print-cmd"], &sandbox.repo);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), format!("cd '{}'\n", worktree.display()));
+
+    let output = sandbox.gj(&["cd", "@"], &worktree);
+    assert_eq!(assert_path_output(&output), sandbox.repo);
+
+    let output = sandbox.gj(&["exit"], &worktree);
+    assert_eq!(assert_path_output(&output), sandbox.repo);
+    assert!(!worktree.exists());
+}
+
+#[test]
+fn test_failures_exit_nonzero_with_empty_stdout() {
+    let sandbox = Sandbox::new();
+
+    assert_failure(&sandbox.gj(&["cd", "no-such-worktree"], &sandbox.repo));
+    // Not inside a gj worktree
+    assert_failure(&sandbox.gj(&["exit"], &sandbox.repo));
+    assert_failure(&sandbox.gj(&["cd", "@"], &sandbox.repo));
+
+    // A worktree path too long to be safe
+    let output = sandbox.gj(&["new", &"long-name-".repeat(30)], &sandbox.repo);
+    assert_failure(&output);
+    assert!(stderr(&output).contains("Worktree path is too long"));
+
+    // Creating the same worktree twice
+    assert_path_output(&sandbox.gj(&["new", "twice"], &sandbox.repo));
+    assert_failure(&sandbox.gj(&["new", "twice"], &sandbox.repo));
+}
+
+#[test]
+fn test_exit_refuses_uncommitted_changes() {
+    let sandbox = Sandbox::new();
+    let worktree = assert_path_output(&sandbox.gj(&["new", "dirty"], &sandbox.repo));
+    fs::write(worktree.join("README.md"), "changed\n").unwrap();
+
+    assert_failure(&sandbox.gj(&["exit"], &worktree));
+    assert!(worktree.exists());
+
+    let output = sandbox.gj(&["exit", "--force"], &worktree);
+    assert_eq!(assert_path_output(&output), sandbox.repo);
+}
+
+#[test]
+fn test_exit_stash_porcelain_reports_the_stash() {
+    let sandbox = Sandbox::new();
+    let worktree = assert_path_output(&sandbox.gj(&["new", "stashed"], &sandbox.repo));
+    fs::write(worktree.join("README.md"), "changed\n").unwrap();
+
+    let output = sandbox.gj(&["exit", "--stash", "--porcelain"], &worktree);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(!worktree.exists());
+
+    let stash = sandbox.git(&["rev-parse", "refs/stash"]);
+    assert_eq!(
+        stdout(&output),
+        format!("path={}\nstash={}", sandbox.repo.display(), stdout(&stash))
+    );
+}
+
+#[test]
+fn test_new_inside_worktree_branches_from_origin() {
+    let sandbox = Sandbox::new();
+    let first = assert_path_output(&sandbox.gj(&["new", "first"], &sandbox.repo));
+
+    // A commit that exists only on the first worktree's branch
+    fs::write(first.join("first.txt"), "first\n").unwrap();
+    for args in [&["add", "first.txt"][..], &["commit", "-q", "-m", "first"]] {
+        let output = sandbox.command("git", &first).args(args).output().unwrap();
+        assert!(output.status.success(), "git {:?}: {:?}", args, output);
+    }
+
+    let output = sandbox.gj(&["new", "second"], &first);
+    let second = assert_path_output(&output);
+    assert!(stderr(&output).contains("using origin repository"));
+    assert!(!second.starts_with(&first));
+    assert!(!second.join("first.txt").exists());
+
+    // The second worktree belongs to the origin repository, not to the first worktree
+    let output = sandbox.gj(&["exit"], &second);
+    assert_eq!(assert_path_output(&output), sandbox.repo);
+}
+
+#[test]
+fn test_merge_conflict_without_terminal_aborts() {
+    let sandbox = Sandbox::new();
+    let worktree = assert_path_output(&sandbox.gj(&["new", "conflict"], &sandbox.repo));
+
+    // Change the same line on both sides
+    for (dir, content) in [(&worktree, "theirs\n"), (&sandbox.repo, "ours\n")] {
+        fs::write(dir.join("README.md"), content).unwrap();
+        let output = sandbox
+            .command("git", dir)
+            .args(["commit", "-q", "-a", "-m", content.trim()])
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{:?}", output);
+    }
+
+    // stdin is not a terminal, so there is no prompt and the merge is aborted
+    assert_failure(&sandbox.gj(&["exit", "--merge"], &worktree));
+    assert!(worktree.exists());
+    assert!(!sandbox.repo.join(".git").join("MERGE_HEAD").exists());
+    assert_eq!(
+        fs::read_to_string(sandbox.repo.join("README.md")).unwrap(),
+        "ours\n"
+    );
+}
+
+/// Cancelling the keep-conflicts prompt (Esc) on a terminal, which `script` provides
+#[cfg(target_os = "linux")]
+#[test]
+fn test_merge_conflict_prompt_cancelled_aborts() {
+    if Command::new("script").arg("--version").output().is_err() {
+        eprintln!("skipped: script(1) is not available");
+        return;
+    }
+
+    let sandbox = Sandbox::new();
+    let worktree = assert_path_output(&sandbox.gj(&["new", "cancel"], &sandbox.repo));
+    for (dir, content) in [(&worktree, "theirs\n"), (&sandbox.repo, "ours\n")] {
+        fs::write(dir.join("README.md"), content).unwrap();
+        let output = sandbox
+            .command("git", dir)
+            .args(["commit", "-q", "-a", "-m", content.trim()])
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{:?}", output);
+    }
+
+    let exit = format!("'{}' exit --merge", env!("CARGO_BIN_EXE_gj"));
+    let output = sandbox
+        .command("sh", &worktree)
+        .arg("-c")
+        .arg(format!(
+            "(sleep 2; printf '\\033') | script -qec \"{}\" /dev/null",
+            exit
+        ))
+        .env("SHELL", "/bin/sh")
+        .output()
+        .unwrap();
+
+    // script reports gj's exit status and copies the terminal to stdout
+    assert!(!output.status.success());
+    assert!(stdout(&output).contains("canceled"), "{}", stdout(&output));
+    assert!(stdout(&output).contains("Aborting merge"));
+    assert!(worktree.exists());
+    assert!(!sandbox.repo.join(".git").join("MERGE_HEAD").exists());
+    assert_eq!(
+        fs::read_to_string(sandbox.repo.join("README.md")).unwrap(),
+        "ours\n"
+    );
+}
+
+#[test]
+fn test_relink_after_moving_origin() {
+    let sandbox = Sandbox::new();
+    let worktree = assert_path_output(&sandbox.gj(&["new", "moved"], &sandbox.repo));
+
+    let moved = sandbox.repo.with_file_name("moved-repo");
+    fs::rename(&sandbox.repo, &moved).unwrap();
+    let output = sandbox.gj(&["cd", "@"], &worktree);
+    assert_failure(&output);
+    assert!(stderr(&output).contains("gj relink"));
+
+    let output = sandbox.gj(&["relink"], &moved);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "");
+
+    let output = sandbox.gj(&["cd", "@"], &worktree);
+    assert_eq!(assert_path_output(&output), moved);
+    let output = sandbox.gj(&["exit"], &worktree);
+    assert_eq!(assert_path_output(&output), moved);
+    assert!(!worktree.exists());
+}
+
+#[test]
+fn test_exit_several_worktrees() {
+    let sandbox = Sandbox::new();
+    let first = assert_path_output(&sandbox.gj(&["new", "first"], &sandbox.repo));
+    let dirty = assert_path_output(&sandbox.gj(&["new", "dirty"], &sandbox.repo));
+    let merged = assert_path_output(&sandbox.gj(&["new", "merged"], &sandbox.repo));
+    fs::write(dirty.join("README.md"), "changed\n").unwrap();
+    let name = |path: &Path| path.file_name().unwrap().to_str().unwrap().to_string();
+
+    // One unknown name fails the command, but the others are still handled
+    let output = sandbox.gj(
+        &["exit", &name(&first), &name(&dirty), "no-such-worktree"],
+        &sandbox.repo,
+    );
+    assert_failure(&output);
+    assert!(!first.exists());
+    assert!(dirty.exists());
+    assert!(stderr(&output).contains("Skipping"));
+
+    // Branches without commits of their own are merged; the shell leaves the removed directory
+    let output = sandbox.gj(&["exit", "--all-merged"], &merged);
+    assert_eq!(assert_path_output(&output), sandbox.repo);
+    assert!(!merged.exists());
+    assert!(dirty.exists());
+}
+
+#[test]
+fn test_new_from_file_prints_no_path() {
+    let sandbox = Sandbox::new();
+    let existing = assert_path_output(&sandbox.gj(&["new", "PROJ-1"], &sandbox.repo));
+    let list = sandbox.home.join("tickets.txt");
+    fs::write(&list, "# tickets\nPROJ-1\n\nPROJ-2\nbad..name\nPROJ-3\n").unwrap();
+
+    // The invalid name fails the command, but the others are still created
+    let output = sandbox.gj(
+        &["new", "--from-file", list.to_str().unwrap()],
+        &sandbox.repo,
+    );
+    assert_failure(&output);
+    assert!(existing.exists());
+    assert!(stderr(&output).contains("Skipped"));
+    assert!(stderr(&output).contains("Created 2 worktree(s); skipped 1, failed 1"));
+    let created = fs::read_dir(existing.parent().unwrap()).unwrap().count();
+    assert_eq!(created, 3);
+
+    fs::write(&list, "PROJ-1\nPROJ-2\n").unwrap();
+    let output = sandbox.gj(
+        &["new", "--from-file", list.to_str().unwrap()],
+        &sandbox.repo,
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn test_new_copies_gitignored_files() {
+    let sandbox = Sandbox::new();
+    let config = sandbox.home.join(".gj").join("config.toml");
+    let mut content = fs::read_to_string(&config).unwrap();
+    content.push_str("copy_gitignored = true\ncopy_gitignored_exclude = [\"node_modules\"]\n");
+    fs::write(&config, content).unwrap();
+
+    fs::write(sandbox.repo.join(".gitignore"), ".env\nnode_modules/\n").unwrap();
+    fs::write(sandbox.repo.join(".env"), "TOKEN=1\n").unwrap();
+    fs::create_dir_all(sandbox.repo.join("node_modules").join("pkg")).unwrap();
+    for args in [
+        &["add", ".gitignore"][..],
+        &["commit", "-q", "-m", "ignore"],
+    ] {
+        let output = sandbox.git(args);
+        assert!(output.status.success(), "git {:?}: {:?}", args, output);
+    }
+
+    let worktree = assert_path_output(&sandbox.gj(&["new", "env"], &sandbox.repo));
+    assert_eq!(
+        fs::read_to_string(worktree.join(".env")).unwrap(),
+        "TOKEN=1\n"
+    );
+    assert!(!worktree.join("node_modules").exists());
+}
+
+/// Register the sandbox repository with `seed_branch = "scaffold"`
+fn register_seed_branch(sandbox: &Sandbox) {
+    let config = sandbox.home.join(".gj").join("config.toml");
+    let mut content = fs::read_to_string(&config).unwrap();
+    content.push_str(&format!(
+        "\n[repos.repo]\npath = \"{}\"\nseed_branch = \"scaffold\"\n",
+        sandbox.repo.display()
+    ));
+    fs::write(&config, content).unwrap();
+}
+
+#[test]
+fn test_new_merges_orphan_seed_branch() {
+    let sandbox = Sandbox::new();
+    register_seed_branch(&sandbox);
+
+    // A scaffold branch sharing no history with main
+    for args in [
+        &["checkout", "-q", "--orphan", "scaffold"][..],
+        &["rm", "-q", "-r", "--cached", "."],
+        &["clean", "-q", "-f"],
+    ] {
+        let output = sandbox.git(args);
+        assert!(output.status.success(), "git {:?}: {:?}", args, output);
+    }
+    fs::write(sandbox.repo.join("SCAFFOLD.md"), "boilerplate\n").unwrap();
+    for args in [
+        &["add", "SCAFFOLD.md"][..],
+        &["commit", "-q", "-m", "scaffold"],
+        &["checkout", "-q", "main"],
+    ] {
+        let output = sandbox.git(args);
+        assert!(output.status.success(), "git {:?}: {:?}", args, output);
+    }
+
+    let output = sandbox.gj(&["new", "seeded"], &sandbox.repo);
+    let worktree = assert_path_output(&output);
+    assert!(stderr(&output).contains("Merged seed branch: scaffold"));
+    assert_eq!(
+        fs::read_to_string(worktree.join("SCAFFOLD.md")).unwrap(),
+        "boilerplate\n"
+    );
+    assert_eq!(
+        fs::read_to_string(worktree.join("README.md")).unwrap(),
+        "hello\n"
+    );
+    let subject = sandbox
+        .command("git", &worktree)
+        .args(["log", "-1", "--format=%s"])
+        .output()
+        .unwrap();
+    assert!(stdout(&subject).starts_with("Merge seed branch 'scaffold' into "));
+}
+
+#[test]
+fn test_new_aborts_conflicting_seed_merge() {
+    let sandbox = Sandbox::new();
+    register_seed_branch(&sandbox);
+
+    // scaffold and main change README.md in different ways
+    for (branch, content) in [("scaffold", "from scaffold\n"), ("main", "from main\n")] {
+        let checkout: &[&str] = if branch == "main" {
+            &["checkout", "-q", "main"]
+        } else {
+            &["checkout", "-q", "-b", "scaffold"]
+        };
+        let output = sandbox.git(checkout);
+        assert!(output.status.success(), "{:?}", output);
+        fs::write(sandbox.repo.join("README.md"), content).unwrap();
+        let output = sandbox.git(&["commit", "-q", "-am", branch]);
+        assert!(output.status.success(), "{:?}", output);
+    }
+
+    let output = sandbox.gj(&["new", "conflicted"], &sandbox.repo);
+    let worktree = assert_path_output(&output);
+    assert!(stderr(&output).contains("Could not merge seed branch 'scaffold'"));
+    assert!(stderr(&output).contains("The merge was aborted"));
+
+    // The branch is left as created: no merge in progress and no changes
+    let status = sandbox
+        .command("git", &worktree)
+        .args(["status", "--porcelain"])
+        .output()
+        .unwrap();
+    assert_eq!(stdout(&status), "");
+    let merge_head = sandbox
+        .command("git", &worktree)
+        .args(["rev-parse", "-q", "--verify", "MERGE_HEAD"])
+        .output()
+        .unwrap();
+    assert!(!merge_head.status.success());
+    assert_eq!(
+        fs::read_to_string(worktree.join("README.md")).unwrap(),
+        "from main\n"
+    );
+}
+
+#[test]
+fn test_new_without_name_or_terminal_does_not_prompt() {
+    let sandbox = Sandbox::new();
+
+    // stdin is not a terminal here, as in CI
+    let output = sandbox.gj(&["new"], &sandbox.repo);
+    let worktree = assert_path_output(&output);
+    assert!(stderr(&output).contains("stdin is not a terminal"));
+    assert!(worktree.starts_with(&sandbox.base_dir));
+
+    let output = sandbox
+        .command(env!("CARGO_BIN_EXE_gj"), &sandbox.repo)
+        .arg("new")
+        .env("GJ_DEFAULT_NAME", "ci build")
+        .output()
+        .unwrap();
+    let worktree = assert_path_output(&output);
+    assert!(worktree.to_string_lossy().ends_with("_ci-build"));
+}
+
+/// Put a stub gh on PATH reporting PR #7 with head branch `feature` in this repository
+/// and return that PATH
+#[cfg(unix)]
+fn stub_gh(sandbox: &Sandbox) -> String {
+    use std::os::unix::fs::PermissionsExt;
+
+    let bin = sandbox.home.join("bin");
+    fs::create_dir_all(&bin).unwrap();
+    let gh = bin.join("gh");
+    fs::write(
+        &gh,
+        "#!/bin/sh\n\
+         case \"$1\" in\n\
+         --version) echo 'gh version 2.40.0' ;;\n\
+         pr) echo '{\"headRefName\":\"feature\",\"isCrossRepository\":false}' ;;\n\
+         *) exit 1 ;;\n\
+         esac\n",
+    )
+    .unwrap();
+    fs::set_permissions(&gh, fs::Permissions::from_mode(0o755)).unwrap();
+
+    format!("{}:{}", bin.display(), std::env::var("PATH").unwrap())
+}
+
+/// Run `gj pr 7 --offline` plus `args` with the stub gh
+#[cfg(unix)]
+fn gj_pr(sandbox: &Sandbox, path: &str, args: &[&str]) -> Output {
+    sandbox
+        .command(env!("CARGO_BIN_EXE_gj"), &sandbox.repo)
+        .args(["pr", "7", "--offline"])
+        .args(args)
+        .env("PATH", path)
+        .output()
+        .unwrap()
+}
+
+#[cfg(unix)]
+#[test]
+fn test_pr_reuses_existing_local_branch() {
+    let sandbox = Sandbox::new();
+    let path = stub_gh(&sandbox);
+
+    // The PR branch exists locally already, with its pushed counterpart on origin
+    for args in [
+        &["branch", "feature"][..],
+        &["update-ref", "refs/remotes/origin/feature", "feature"],
+    ] {
+        let output = sandbox.git(args);
+        assert!(output.status.success(), "git {:?}: {:?}", args, output);
+    }
+
+    let output = gj_pr(&sandbox, &path, &[]);
+    let worktree = assert_path_output(&output);
+    assert!(stderr(&output).contains("Using existing local branch 'feature'"));
+
+    let git_in_worktree = |args: &[&str]| {
+        let output = sandbox
+            .command("git", &worktree)
+            .args(args)
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap().trim().to_string()
+    };
+    assert_eq!(git_in_worktree(&["branch", "--show-current"]), "feature");
+    assert_eq!(
+        git_in_worktree(&["rev-parse", "--abbrev-ref", "feature@{upstream}"]),
+        "origin/feature"
+    );
+}
+
+/// Point `origin/feature` at a new commit on top of main with `message`; return its SHA
+#[cfg(unix)]
+fn push_feature(sandbox: &Sandbox, message: &str) -> String {
+    let output = sandbox.git(&["commit-tree", "HEAD^{tree}", "-p", "HEAD", "-m", message]);
+    assert!(output.status.success(), "{:?}", output);
+    let commit = stdout(&output).trim().to_string();
+    let output = sandbox.git(&["update-ref", "refs/remotes/origin/feature", &commit]);
+    assert!(output.status.success(), "{:?}", output);
+    commit
+}
+
+#[cfg(unix)]
+#[test]
+fn test_pr_force_recreate_after_force_push() {
+    let sandbox = Sandbox::new();
+    let path = stub_gh(&sandbox);
+    push_feature(&sandbox, "feature");
+    let worktree = assert_path_output(&gj_pr(&sandbox, &path, &[]));
+
+    // The PR is force-pushed, so the old head is no ancestor of the new one
+    let rewritten = push_feature(&sandbox, "feature, amended");
+    let output = gj_pr(&sandbox, &path, &["--force-recreate"]);
+    assert_eq!(assert_path_output(&output), worktree);
+    assert!(stderr(&output).contains("Removed stale worktree"));
+    let output = sandbox
+        .command("git", &worktree)
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .unwrap();
+    assert_eq!(stdout(&output).trim(), rewritten);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_pr_force_recreate_refuses_unpushed_commits() {
+    let sandbox = Sandbox::new();
+    let path = stub_gh(&sandbox);
+    push_feature(&sandbox, "feature");
+    let worktree = assert_path_output(&gj_pr(&sandbox, &path, &[]));
+
+    // A commit that was never on origin
+    fs::write(worktree.join("local.txt"), "local\n").unwrap();
+    for args in [&["add", "local.txt"][..], &["commit", "-q", "-m", "local"]] {
+        let output = sandbox
+            .command("git", &worktree)
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?}: {:?}", args, output);
+    }
+
+    let output = gj_pr(&sandbox, &path, &["--force-recreate"]);
+    assert_failure(&output);
+    assert!(stderr(&output).contains("1 commit(s) that were never on origin/feature"));
+    assert!(worktree.join("local.txt").exists());
+}
+
+#[test]
+fn test_checkout_several_branches() {
+    let sandbox = Sandbox::new();
+    for branch in ["feature/a", "feature/b"] {
+        let reference = format!("refs/remotes/origin/{}", branch);
+        let output = sandbox.git(&["update-ref", &reference, "HEAD"]);
+        assert!(output.status.success(), "{:?}", output);
+    }
+
+    // A missing branch fails the command, but the others are still checked out
+    let output = sandbox.gj(
+        &[
+            "checkout",
+            "--offline",
+            "feature/a",
+            "no-such-branch",
+            "origin/feature/b",
+        ],
+        &sandbox.repo,
+    );
+    assert_failure(&output);
+    assert!(stderr(&output).contains("Checked out 2 of 3 branches"));
+    let repo_dir = sandbox.base_dir.join("github.com").join("me").join("repo");
+    assert!(repo_dir.join("feature/a").is_dir());
+    assert!(repo_dir.join("feature/b").is_dir());
+
+    // A single branch still prints its path for the shell wrapper
+    let reference = "refs/remotes/origin/feature/c";
+    assert!(sandbox
+        .git(&["update-ref", reference, "HEAD"])
+        .status
+        .success());
+    let output = sandbox.gj(&["checkout", "--offline", "feature/c"], &sandbox.repo);
+    assert_eq!(assert_path_output(&output), repo_dir.join("feature/c"));
+}
+
+#[test]
+fn test_new_relative_base_dir_is_resolved_where_gj_runs() {
+    let sandbox = Sandbox::new();
+    let sub = sandbox.repo.join("sub");
+    fs::create_dir_all(&sub).unwrap();
+
+    // gj changes into the repository root before creating the worktree
+    let output = sandbox.gj(&["new", "rel", "--base-dir", "rel"], &sub);
+    let worktree = assert_path_output(&output);
+    assert!(
+        worktree.starts_with(sub.join("rel")),
+        "{}",
+        worktree.display()
+    );
+
+    // The state records the absolute path, so the worktree is found from anywhere
+    let name = worktree.file_name().unwrap().to_str().unwrap();
+    let output = sandbox.gj(&["cd", name], &sandbox.home);
+    assert_eq!(assert_path_output(&output), worktree);
+}